@@ -0,0 +1,20 @@
+//! # Aliasing Example
+//!
+//! https://github.com/AllenDowney/ThinkDSP/blob/f1cc15de31f658d5df287332a30659fb16eb41d5/code/chap02.ipynb
+
+use thinkdsp::book::*;
+use thinkdsp::error::Result;
+use thinkdsp::render::Style;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> Result<()> {
+    println!("Plots the samples of a 440 Hz cosine taken at 11025 and 800 Hz");
+
+    let cosine = CosSignal::new(440.0, 1.0, 0.0)?;
+
+    // Well above the Nyquist rate: the samples follow the signal.
+    cosine.plot_samples(11025, Style::Stems)?;
+
+    // Below it: the samples trace a 360 Hz cosine instead.
+    cosine.plot_samples(800, Style::Stems)
+}
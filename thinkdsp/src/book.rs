@@ -2,12 +2,63 @@
 //! The intention was to remain as faithful as possible to the original implementation.
 
 #![allow(unused)]
-use std::{f32::consts::TAU, ops::Add};
+use std::{
+    borrow::Cow,
+    f64::consts::TAU,
+    fmt::Debug,
+    iter::Sum,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub},
+};
 
-use crate::render;
+pub use num_complex::Complex;
+use num_traits::{Float, FloatConst};
+
+use crate::error::{
+    Error, Result, check_duration, check_framerate, check_frequency, check_len, check_range,
+    check_time, check_times,
+};
+use crate::render::{self, Backend, Series, Style, Times};
+
+pub mod dft;
+pub mod stats;
+
+/// Floating-point type of the samples of signals and waves: `f32` or `f64`.
+///
+/// Times are always `f64`, whatever the sample type, see [`Signal::make_wave`].
+pub trait Sample:
+    Float + FloatConst + Default + Debug + Sum + AddAssign + MulAssign + Send + Sync + 'static
+{
+    /// Converts from `f64`, rounding to the nearest value if needed.
+    fn from_f64(x: f64) -> Self;
+
+    /// Converts to `f64`.
+    fn into_f64(self) -> f64;
+}
+
+impl Sample for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+
+    fn into_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Sample for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+
+    fn into_f64(self) -> f64 {
+        self
+    }
+}
 
 /// Represents a time-varying signal.
-pub trait Signal {
+///
+/// `T` is the type of the samples, see [`Sample`].
+pub trait Signal<T: Sample = f32> {
     /// Period of the signal.
     ///
     /// Since this is used primarily for purposes of plotting,
@@ -15,19 +66,48 @@ pub trait Signal {
     /// that is reasonable for many signals.
     ///
     /// # Returns
-    /// * `f32` - The period of the signal in seconds.
-    fn period(&self) -> f32 {
+    /// * `f64` - The period of the signal in seconds.
+    fn period(&self) -> f64 {
         0.1
     }
 
+    /// Whether the signal repeats and, if so, its fundamental period.
+    ///
+    /// The default behavior trusts [`Signal::period`]; signals built out of
+    /// other signals override it to combine the periods of their components.
+    ///
+    /// # Returns
+    /// * `Periodicity` - The fundamental period, or `Aperiodic`.
+    fn periodicity(&self) -> Periodicity {
+        Periodicity::Periodic(self.period())
+    }
+
     /// Plots the signal.
     /// The default behavior is to plot three periods.
     ///
     /// # Arguments
     /// * `framerate` - The number of samples per second.
-    fn plot(&self, framerate: u64) {
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the wave can't be made or drawn.
+    fn plot(&self, framerate: u64) -> Result<()> {
+        let duration = self.period() * 3.0;
+        self.make_wave(duration, 0.0, framerate)?.plot()
+    }
+
+    /// Plots the samples taken at `framerate` over the continuous signal.
+    /// Like [`Signal::plot`], it shows three periods.
+    ///
+    /// # Arguments
+    /// * `framerate` - The number of samples per second.
+    /// * `style` - How to draw the samples, usually [`Style::Stems`].
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the wave can't be made or drawn.
+    fn plot_samples(&self, framerate: u64, style: Style) -> Result<()> {
         let duration = self.period() * 3.0;
-        self.make_wave(duration, 0.0, framerate).plot();
+        self.make_wave(duration, 0.0, framerate)?
+            .plot_over(self, style, Backend::from_env())
     }
 
     /// Evaluates the signal at the given times.
@@ -36,11 +116,24 @@ pub trait Signal {
     /// * `ts` - A vector of time points (in seconds) at which to evaluate the signal.
     ///
     /// # Returns
-    /// * `Vec<f32>` - The values of the signal at the given time points.
+    /// * `Vec<T>` - The values of the signal at the given time points.
     ///
     /// NOTE: In the book's implementation subclasses as SumSinusoid implements `evaluate` and because Python is dynamic
     /// call `evaluate` from `make_wave` works, in Rust we need to be explicit and add it as part of the trait.
-    fn evaluate(&self, ts: &Vec<f32>) -> Vec<f32>;
+    fn evaluate(&self, ts: &[f64]) -> Vec<T>;
+
+    /// Evaluates the signal at the given times into a caller-provided buffer.
+    ///
+    /// The default behavior copies the result of [`Signal::evaluate`];
+    /// signals that can write their samples in place override it, so
+    /// [`Stream`] runs without allocating.
+    ///
+    /// # Arguments
+    /// * `ts` - The time points (in seconds) at which to evaluate the signal.
+    /// * `out` - Where to write the values, as long as `ts`.
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        out.copy_from_slice(&self.evaluate(ts));
+    }
 
     /// Creates a wave from the signal.
     ///
@@ -50,181 +143,3005 @@ pub trait Signal {
     /// * `framerate` - The number of frames per second.
     ///
     /// # Returns
-    /// * `Wave` - The generated wave.
-    fn make_wave(&self, duration: f32, start: f32, framerate: u64) -> Wave {
-        let n = (duration * framerate as f32).round() as usize;
-        let ts: Vec<f32> = (0..n)
-            .map(|i| start + i as f32 / framerate as f32)
-            .collect();
+    /// * `Result<Wave<T>>` - The generated wave, or an error for a zero
+    ///   framerate, a negative duration or a time that isn't finite.
+    fn make_wave(&self, duration: f64, start: f64, framerate: u64) -> Result<Wave<T>> {
+        let ts = sample_times(duration, start, framerate)?;
         let ys = self.evaluate(&ts);
 
-        Wave { ts, ys, framerate }
+        Ok(Wave {
+            ys,
+            time: TimeAxis::Uniform { start },
+            framerate,
+        })
     }
-}
 
-/// Represents a discrete-time waveform.
-pub struct Wave {
-    ys: Vec<f32>,
-    ts: Vec<f32>,
-    pub framerate: u64,
-}
+    /// Generates the samples of the signal lazily, for an unbounded duration.
+    ///
+    /// The samples are the ones [`Signal::make_wave`] would produce with the
+    /// same `start` and `framerate`, however they are pulled, one at a time
+    /// or in blocks of any size, see [`Stream`].
+    ///
+    /// # Arguments
+    /// * `start` - The time of the first sample in seconds.
+    /// * `framerate` - The number of frames per second.
+    ///
+    /// # Returns
+    /// * `Result<Stream<&Self, T>>` - The stream of samples, or an error for
+    ///   a zero framerate or a start that isn't finite.
+    fn stream(&self, start: f64, framerate: u64) -> Result<Stream<&Self, T>>
+    where
+        Self: Sized,
+    {
+        Stream::new(self, start, framerate)
+    }
 
-impl Wave {
-    /// Creates a wave.
+    /// Multiplies the signal by a constant.
     ///
     /// # Arguments
-    /// * `ys` - wave array
-    /// * `ts` - array of times
-    /// * `framerate` - samples per second
+    /// * `factor` - The gain.
     ///
     /// # Returns
-    /// * `Wave` - The generated wave.
-    pub fn new(ys: Vec<f32>, ts: Vec<f32>, framerate: u64) -> Self {
-        Self { ys, ts, framerate }
+    /// * `Scaled<Self, T>` - The scaled signal.
+    fn scale(self, factor: T) -> Scaled<Self, T>
+    where
+        Self: Sized,
+    {
+        Scaled(self, factor)
     }
 
-    /// Plots the wave.
-    pub fn plot(&self) {
-        render::render(self.ts.clone(), self.ys.clone());
+    /// Shifts the signal later in time.
+    ///
+    /// # Arguments
+    /// * `seconds` - The delay; negative values move the signal earlier.
+    ///
+    /// # Returns
+    /// * `Result<Delayed<Self>>` - The delayed signal, or an error if the delay isn't finite.
+    fn delay(self, seconds: f64) -> Result<Delayed<Self>>
+    where
+        Self: Sized,
+    {
+        check_time(seconds)?;
+        Ok(Delayed(self, seconds))
     }
 
-    /// Returns the length of the wave.
+    /// Compresses or stretches the signal in time.
+    ///
+    /// # Arguments
+    /// * `factor` - Speed-up factor: 2.0 plays the signal twice as fast
+    ///   (halving its period), 0.5 stretches it to twice as long and a
+    ///   negative factor plays it backwards.
     ///
     /// # Returns
-    /// * `usize` - Length of samples array.
-    pub fn len(&self) -> usize {
-        self.ys.len()
+    /// * `Result<TimeScaled<Self>>` - The time-scaled signal, or an error if
+    ///   the factor is zero, which would freeze the signal, or isn't finite.
+    fn time_scale(self, factor: f64) -> Result<TimeScaled<Self>>
+    where
+        Self: Sized,
+    {
+        if factor == 0.0 || !factor.is_finite() {
+            return Err(Error::OutOfRange {
+                name: "factor",
+                value: factor,
+            });
+        }
+        Ok(TimeScaled(self, factor))
+    }
+
+    /// Places the signal in a stereo field, see [`Panned`].
+    ///
+    /// # Arguments
+    /// * `position` - From -1 (left) through 0 (center) to 1 (right).
+    ///
+    /// # Returns
+    /// * `Panned<Self, T>` - The panned signal.
+    fn pan(self, position: T) -> Panned<Self, T>
+    where
+        Self: Sized,
+    {
+        Panned::new(self, position)
+    }
+
+    /// Boxes the signal, so signals of different types can be stored together.
+    ///
+    /// # Returns
+    /// * `Box<dyn Signal<T>>` - The boxed signal.
+    fn boxed(self) -> Box<dyn Signal<T>>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
     }
 }
 
-/// Represents a sinusoidal signal.
-pub struct Sinusoid {
-    freq: f32,
-    amp: f32,
-    offset: f32,
-    func: fn(f32) -> f32,
+impl<T: Sample, S: Signal<T> + ?Sized> Signal<T> for Box<S> {
+    fn period(&self) -> f64 {
+        (**self).period()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        (**self).periodicity()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        (**self).evaluate(ts)
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        (**self).evaluate_into(ts, out)
+    }
 }
 
-impl Sinusoid {
-    /// Creates a sinusoidal signal.
+impl<T: Sample, S: Signal<T> + ?Sized> Signal<T> for &S {
+    fn period(&self) -> f64 {
+        (**self).period()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        (**self).periodicity()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        (**self).evaluate(ts)
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        (**self).evaluate_into(ts, out)
+    }
+}
+
+/// Times of the samples of a wave, see [`Signal::make_wave`].
+fn sample_times(duration: f64, start: f64, framerate: u64) -> Result<Vec<f64>> {
+    check_framerate(framerate)?;
+    check_duration(duration)?;
+    check_time(start)?;
+    let n = (duration * framerate as f64).round() as usize;
+    Ok((0..n)
+        .map(|i| start + i as f64 / framerate as f64)
+        .collect())
+}
+
+/// A number is a constant signal, so `signal * 0.5` scales a signal and
+/// `signal + 1.0` adds a DC offset to it.
+macro_rules! impl_constant_signal {
+    ($($t:ty),*) => {
+        $(
+            impl Signal<$t> for $t {
+                fn periodicity(&self) -> Periodicity {
+                    Periodicity::Periodic(f64::INFINITY)
+                }
+
+                fn evaluate(&self, ts: &[f64]) -> Vec<$t> {
+                    vec![*self; ts.len()]
+                }
+
+                fn evaluate_into(&self, _ts: &[f64], out: &mut [$t]) {
+                    out.fill(*self);
+                }
+            }
+        )*
+    };
+}
+
+impl_constant_signal!(f32, f64);
+
+/// Largest phase mismatch, in cycles, for two components to count as repeating together.
+pub const PERIOD_TOLERANCE: f64 = 1e-4;
+
+/// Longest fundamental period searched for, in seconds.
+pub const MAX_PERIOD: f64 = 2.0;
+
+/// Whether a signal repeats, and how often.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Periodicity {
+    /// The signal repeats every given number of seconds.
     ///
-    /// # Arguments
-    /// * `freq` - The frequency of the signal in Hz.
-    /// * `amp` - The amplitude of the signal.
-    /// * `offset` - The phase offset in radians.
-    /// * `func` - The function that maps phase to amplitude.
+    /// An infinite period stands for a constant signal, e.g. a constant or a [`SilentSignal`].
+    Periodic(f64),
+    /// No common period was found within [`MAX_PERIOD`].
+    Aperiodic,
+}
+
+impl Periodicity {
+    /// Returns the period, if any.
     ///
     /// # Returns
-    /// * `Sinusoid` - The generated sinusoidal signal.
-    pub fn new(freq: f32, amp: f32, offset: f32, func: fn(f32) -> f32) -> Sinusoid {
-        Sinusoid {
-            freq,
-            amp,
-            offset,
-            func,
+    /// * `Option<f64>` - The period in seconds.
+    pub fn period(self) -> Option<f64> {
+        match self {
+            Periodicity::Periodic(period) => Some(period),
+            Periodicity::Aperiodic => None,
         }
     }
-}
 
-impl Signal for Sinusoid {
-    fn period(&self) -> f32 {
-        1.0 / self.freq
+    /// Fundamental period of two components played together,
+    /// using [`PERIOD_TOLERANCE`] and [`MAX_PERIOD`].
+    ///
+    /// # Arguments
+    /// * `other` - The periodicity of the other component.
+    ///
+    /// # Returns
+    /// * `Periodicity` - The periodicity of the sum.
+    pub fn combine(self, other: Periodicity) -> Periodicity {
+        self.combine_within(other, PERIOD_TOLERANCE, MAX_PERIOD)
     }
 
-    fn evaluate(&self, ts: &Vec<f32>) -> Vec<f32> {
-        ts.iter()
-            .map(|&t| {
-                let phase = TAU * self.freq * t + self.offset;
-                self.amp * (self.func)(phase)
-            })
-            .collect()
+    /// Fundamental period of two components played together.
+    ///
+    /// The common period is the least common multiple of both periods, i.e.
+    /// the inverse of the greatest common divisor of both frequencies. It is
+    /// found with the continued fraction expansion of the ratio of the longer
+    /// period to the shorter one, whose convergents `p/q` are the smallest
+    /// fractions closest to it: the first one that lines up both components
+    /// within `tolerance` cycles of the shorter one gives the period
+    /// `q * longer`. The result doesn't depend on the order of the components.
+    ///
+    /// # Arguments
+    /// * `other` - The periodicity of the other component.
+    /// * `tolerance` - Largest phase mismatch allowed, in cycles.
+    /// * `max_period` - Longest period accepted, in seconds.
+    ///
+    /// # Returns
+    /// * `Periodicity` - The periodicity of the sum.
+    pub fn combine_within(
+        self,
+        other: Periodicity,
+        tolerance: f64,
+        max_period: f64,
+    ) -> Periodicity {
+        use Periodicity::*;
+
+        let (a, b) = match (self, other) {
+            (Periodic(a), Periodic(b)) if a > 0.0 && b > 0.0 => (a, b),
+            _ => return Aperiodic,
+        };
+        // A constant component doesn't change the period of the other one.
+        if a.is_infinite() {
+            return other;
+        }
+        if b.is_infinite() {
+            return self;
+        }
+
+        // With the longer period first the ratio is at least 1, so the first
+        // convergent already has `p >= 1` and `q * ratio - p` is the mismatch
+        // in cycles of the shorter component.
+        let (long, short) = if a >= b { (a, b) } else { (b, a) };
+        let ratio = long / short;
+        let (mut p, mut p_prev) = (1.0, 0.0);
+        let (mut q, mut q_prev) = (0.0, 1.0);
+        let mut x = ratio;
+        loop {
+            let term = x.floor();
+            (p, p_prev) = (term * p + p_prev, p);
+            (q, q_prev) = (term * q + q_prev, q);
+
+            let period = q * long;
+            if period > max_period {
+                return Aperiodic;
+            }
+            if (q * ratio - p).abs() <= tolerance {
+                return Periodic(period);
+            }
+            x = 1.0 / (x - term);
+            if !x.is_finite() {
+                return Aperiodic;
+            }
+        }
     }
 }
 
-/// Represents the sum of two sinusoidal signals
+/// Implements the arithmetic operators for a signal type.
 ///
 /// NOTE: In the original implementation the `add` methods was implemenetd in `Signal` class
-/// but in Rust we can't implement traits for other traits - we can only implement traits for concrete types.
-impl Add for Sinusoid {
-    type Output = SumSignal<Sinusoid>;
+/// but in Rust we can't implement traits for other traits - we can only implement traits for concrete types,
+/// so every signal type in this module opts in through this macro.
+///
+/// The right-hand side isn't bounded by `Signal<T>` because `T` can't be named for
+/// combinators such as `SumSignal<A, B>`; the result is only a signal when both sides are.
+macro_rules! impl_signal_ops {
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<$($generics)* Rhs> Add<Rhs> for $ty {
+            type Output = SumSignal<Self, Rhs>;
 
-    fn add(self, other: Self) -> Self::Output {
-        SumSignal(self, other)
-    }
+            fn add(self, other: Rhs) -> Self::Output {
+                SumSignal(self, other)
+            }
+        }
+
+        impl<$($generics)* Rhs> Sub<Rhs> for $ty {
+            type Output = SumSignal<Self, Negated<Rhs>>;
+
+            fn sub(self, other: Rhs) -> Self::Output {
+                SumSignal(self, Negated(other))
+            }
+        }
+
+        impl<$($generics)* Rhs> Mul<Rhs> for $ty {
+            type Output = Product<Self, Rhs>;
+
+            fn mul(self, other: Rhs) -> Self::Output {
+                Product(self, other)
+            }
+        }
+
+        impl<$($generics)*> Neg for $ty {
+            type Output = Negated<Self>;
+
+            fn neg(self) -> Self::Output {
+                Negated(self)
+            }
+        }
+    };
+    ($ty:ty) => {
+        impl_signal_ops!([] $ty);
+    };
 }
 
-/// Represents a cosine sinusoid.
-pub struct CosSignal(Sinusoid);
+impl_signal_ops!([T: Sample,] Box<dyn Signal<T>>);
 
-impl CosSignal {
-    /// Creates a cosine signal.
+/// How many times denser than the samples the signal is evaluated by [`Wave::plot_over`].
+const DENSE_FACTOR: u64 = 16;
+
+/// Least number of points used to draw the signal in [`Wave::plot_over`].
+const DENSE_MIN_POINTS: usize = 2000;
+
+/// Most points used to draw the signal in [`Wave::plot_over`], far more
+/// than any screen shows even zoomed in.
+const DENSE_MAX_POINTS: usize = 1 << 20;
+
+/// Framerate of the grid [`Wave::plot_over`] evaluates the signal on: [`DENSE_FACTOR`]
+/// times the wave's, but between [`DENSE_MIN_POINTS`] and [`DENSE_MAX_POINTS`] over `duration`.
+pub(crate) fn dense_framerate(framerate: u64, duration: f64) -> u64 {
+    let at_least = (DENSE_MIN_POINTS as f64 / duration).ceil() as u64;
+    let at_most = ((DENSE_MAX_POINTS as f64 / duration).floor() as u64).max(1);
+    framerate
+        .saturating_mul(DENSE_FACTOR)
+        .max(at_least)
+        .min(at_most)
+}
+
+/// Framerate of waves collected from bare samples, as in the book.
+pub const DEFAULT_FRAMERATE: u64 = 11025;
+
+/// Times of the samples of a wave.
+///
+/// Evenly spaced samples only keep the time of the first one; the others
+/// are computed from the framerate of the wave when asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeAxis {
+    /// Sample `i` is at `start + i / framerate`.
+    Uniform { start: f64 },
+    /// One time per sample, for irregularly sampled data.
+    Explicit(Vec<f64>),
+}
+
+impl TimeAxis {
+    /// Creates the axis of the given times, uniform if they are exactly
+    /// `ts[0] + i / framerate`.
     ///
     /// # Arguments
-    /// * `freq` - The frequency of the signal in Hz.
-    /// * `amp` - The amplitude of the signal.
-    /// * `offset` - The phase offset in radians.
+    /// * `ts` - The times of the samples.
+    /// * `framerate` - The number of samples per second.
+    ///
+    /// # Returns
+    /// * `TimeAxis` - The axis, which gives back the same times.
+    pub fn from_times(ts: Vec<f64>, framerate: u64) -> Self {
+        let Some(&start) = ts.first() else {
+            return TimeAxis::Uniform { start: 0.0 };
+        };
+        let uniform = TimeAxis::Uniform { start };
+        if ts
+            .iter()
+            .enumerate()
+            .all(|(i, &t)| t == uniform.time(i, framerate))
+        {
+            uniform
+        } else {
+            TimeAxis::Explicit(ts)
+        }
+    }
+
+    /// Returns the time of sample `i`.
+    ///
+    /// # Arguments
+    /// * `i` - The index of the sample.
+    /// * `framerate` - The number of samples per second.
     ///
     /// # Returns
-    /// * `CosSignal` - The generated cosine signal.
-    pub fn new(freq: f32, amp: f32, offset: f32) -> Self {
-        Self(Sinusoid::new(freq, amp, offset, f32::cos))
+    /// * `f64` - The time in seconds.
+    pub fn time(&self, i: usize, framerate: u64) -> f64 {
+        match self {
+            TimeAxis::Uniform { start } => start + i as f64 / framerate as f64,
+            TimeAxis::Explicit(ts) => ts[i],
+        }
     }
-}
 
-impl From<CosSignal> for Sinusoid {
-    fn from(c: CosSignal) -> Self {
-        c.0
+    /// Returns the time of the first sample, 0 if there are none.
+    pub fn start(&self) -> f64 {
+        match self {
+            TimeAxis::Uniform { start } => *start,
+            TimeAxis::Explicit(ts) => ts.first().copied().unwrap_or(0.0),
+        }
     }
-}
 
-/// Represents a sine sinusoid.
-pub struct SinSignal(Sinusoid);
+    /// Returns the fractional index of time `t`, the inverse of [`TimeAxis::time`].
+    ///
+    /// An explicit axis is searched for the samples around `t`, which must be
+    /// in increasing order, and is linear in between; before the first sample
+    /// and after the last one the samples are taken to be `1 / framerate` apart.
+    ///
+    /// # Arguments
+    /// * `t` - The time in seconds.
+    /// * `framerate` - The number of samples per second.
+    ///
+    /// # Returns
+    /// * `f64` - The index, between `i` and `i + 1` for a time between samples
+    ///   `i` and `i + 1`, NaN for a NaN time.
+    pub fn index(&self, t: f64, framerate: u64) -> f64 {
+        let framerate = framerate as f64;
+        match self {
+            TimeAxis::Uniform { start } => (t - start) * framerate,
+            TimeAxis::Explicit(_) if t.is_nan() => f64::NAN,
+            TimeAxis::Explicit(ts) => match (ts.first(), ts.last()) {
+                (Some(&first), _) if t < first => (t - first) * framerate,
+                (_, Some(&last)) if t >= last => (ts.len() - 1) as f64 + (t - last) * framerate,
+                (None, _) => t * framerate,
+                _ => {
+                    let i = ts.partition_point(|&s| s <= t) - 1;
+                    i as f64 + (t - ts[i]) / (ts[i + 1] - ts[i])
+                }
+            },
+        }
+    }
 
-impl SinSignal {
-    /// Creates a sine signal.
+    /// Returns the times of the first `n` samples for plotting, computed
+    /// while drawing for a uniform axis.
+    pub(crate) fn plot_times(&self, n: usize, framerate: u64) -> Times<'_> {
+        match self {
+            TimeAxis::Uniform { start } => Times::Uniform {
+                start: *start,
+                framerate,
+            },
+            TimeAxis::Explicit(ts) => Times::Explicit(&ts[..n]),
+        }
+    }
+
+    /// Returns the times of the first `n` samples.
     ///
     /// # Arguments
-    /// * `freq` - The frequency of the signal in Hz.
-    /// * `amp` - The amplitude of the signal.
-    /// * `offset` - The phase offset in radians.
+    /// * `n` - The number of samples.
+    /// * `framerate` - The number of samples per second.
     ///
     /// # Returns
-    /// * `SinSignal` - The generated sine signal.
-    pub fn new(freq: f32, amp: f32, offset: f32) -> Self {
-        Self(Sinusoid::new(freq, amp, offset, f32::sin))
+    /// * `Cow<[f64]>` - The times, computed for a uniform axis.
+    pub fn times(&self, n: usize, framerate: u64) -> Cow<'_, [f64]> {
+        match self {
+            TimeAxis::Uniform { .. } => (0..n).map(|i| self.time(i, framerate)).collect(),
+            TimeAxis::Explicit(ts) => Cow::Borrowed(&ts[..n]),
+        }
     }
 }
 
-impl From<SinSignal> for Sinusoid {
-    fn from(s: SinSignal) -> Self {
-        s.0
-    }
+/// Represents a discrete-time waveform.
+///
+/// `T` is the type of the samples, see [`Sample`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wave<T = f32> {
+    ys: Vec<T>,
+    time: TimeAxis,
+    pub framerate: u64,
 }
 
-/// Represents the sum of two signals.
-pub struct SumSignal<T: Signal>(T, T);
+impl<T: Sample> Wave<T> {
+    /// Creates a wave.
+    ///
+    /// # Arguments
+    /// * `ys` - wave array
+    /// * `ts` - array of times
+    /// * `framerate` - samples per second
+    ///
+    /// Evenly spaced times aren't kept, see [`TimeAxis::from_times`].
+    ///
+    /// # Returns
+    /// * `Result<Wave>` - The generated wave, or an error if `ys` and `ts`
+    ///   differ in length, a time isn't finite or is before the previous one,
+    ///   or the framerate is zero.
+    pub fn new(ys: Vec<T>, ts: Vec<f64>, framerate: u64) -> Result<Self> {
+        check_len(ts.len(), ys.len())?;
+        check_times(&ts)?;
+        check_framerate(framerate)?;
+        let time = TimeAxis::from_times(ts, framerate);
+        Ok(Self {
+            ys,
+            time,
+            framerate,
+        })
+    }
 
-impl<T: Signal> Signal for SumSignal<T> {
-    /// Period of the signal.
+    /// Creates a wave of evenly spaced samples.
+    ///
+    /// # Arguments
+    /// * `ys` - wave array
+    /// * `start` - time of the first sample in seconds
+    /// * `framerate` - samples per second
     ///
-    /// NOTE: this is not correct; it's mostly a placekeeper.
+    /// # Returns
+    /// * `Result<Wave>` - The generated wave, or an error if the start time
+    ///   isn't finite or the framerate is zero.
+    pub fn uniform(ys: Vec<T>, start: f64, framerate: u64) -> Result<Self> {
+        check_time(start)?;
+        check_framerate(framerate)?;
+        Ok(Self {
+            ys,
+            time: TimeAxis::Uniform { start },
+            framerate,
+        })
+    }
+
+    /// Plots the wave.
     ///
-    /// But it is correct for a harmonic sequence where all
-    /// component frequencies are multiples of the fundamental.
+    /// The backend is picked from the environment, see [`Backend::from_env`].
     ///
     /// # Returns
-    /// * `f32` - The period of the signal in seconds.
-    fn period(&self) -> f32 {
-        self.0.period().max(self.1.period())
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot(&self) -> Result<()> {
+        self.plot_with(Backend::from_env())
     }
 
-    fn evaluate(&self, ts: &Vec<f32>) -> Vec<f32> {
-        let samples_a = self.0.evaluate(ts);
-        let samples_b = self.1.evaluate(ts);
-        samples_a
-            .into_iter()
-            .zip(samples_b.into_iter())
-            .map(|(a, b)| a + b)
-            .collect()
+    /// Plots the wave with the given backend.
+    ///
+    /// # Arguments
+    /// * `backend` - Where to draw the plot.
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot_with(&self, backend: Backend) -> Result<()> {
+        self.plot_styled(Style::Line, backend)
     }
-}
+
+    /// Plots the wave with the given style, e.g. one stem per sample.
+    ///
+    /// # Arguments
+    /// * `style` - How to draw the samples.
+    /// * `backend` - Where to draw the plot.
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot_styled(&self, style: Style, backend: Backend) -> Result<()> {
+        render::render(backend, &[self.series("wave").style(style)])
+    }
+
+    /// The samples as a series to plot, without computing the times of a uniform axis.
+    fn series<'a>(&'a self, name: &'a str) -> Series<'a, T> {
+        let times = self.time.plot_times(self.ys.len(), self.framerate);
+        Series::with_times(name, times, &self.ys)
+    }
+
+    /// Plots the samples of the wave over the signal they were taken from.
+    ///
+    /// The signal is evaluated on a grid much denser than the wave's, so it
+    /// looks continuous and shows what was lost (or aliased) by sampling.
+    /// The grid is capped at about a million points, so a long wave gets a
+    /// coarser one.
+    ///
+    /// # Arguments
+    /// * `signal` - The signal the wave was sampled from.
+    /// * `style` - How to draw the samples.
+    /// * `backend` - Where to draw the plot.
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot_over<S: Signal<T> + ?Sized>(
+        &self,
+        signal: &S,
+        style: Style,
+        backend: Backend,
+    ) -> Result<()> {
+        if self.is_empty() {
+            return self.plot_styled(style, backend);
+        }
+        let start = self.start();
+        let duration = self.end() - start + 1.0 / self.framerate as f64;
+        let dense_framerate = dense_framerate(self.framerate, duration);
+        let dense = signal.make_wave(duration, start, dense_framerate)?;
+
+        let series = [dense.series("signal"), self.series("samples").style(style)];
+        render::render(backend, &series)
+    }
+
+    /// Samples the wave with an impulse train, as in chapter 11: every
+    /// `factor`-th sample is kept and the ones in between are set to zero.
+    ///
+    /// The result has the same length and framerate, so its spectrum shows
+    /// the copies that sampling at `framerate / factor` makes.
+    ///
+    /// # Arguments
+    /// * `factor` - The spacing of the impulses, in samples.
+    ///
+    /// # Returns
+    /// * `Result<Wave<T>>` - The sampled wave, or an error for a zero factor.
+    pub fn sample(&self, factor: usize) -> Result<Wave<T>> {
+        check_range("factor", factor as f64, 1.0..)?;
+        let ys = self
+            .ys
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| if i % factor == 0 { y } else { T::zero() })
+            .collect();
+        Ok(self.with_ys(ys))
+    }
+
+    /// Turns the wave into a signal that can be evaluated at any time.
+    ///
+    /// The wave itself is a signal too, with linear interpolation and silence around it.
+    ///
+    /// # Arguments
+    /// * `interpolation` - How to compute the signal between the samples.
+    /// * `extension` - What the signal is before and after the wave.
+    ///
+    /// # Returns
+    /// * `Result<Interpolated<T>>` - The signal, or an error for a sinc
+    ///   without zero crossings.
+    pub fn interpolated(
+        self,
+        interpolation: Interpolation,
+        extension: Extension,
+    ) -> Result<Interpolated<T>> {
+        interpolation.check()?;
+        Ok(Interpolated {
+            wave: self,
+            interpolation,
+            extension,
+        })
+    }
+
+    /// Converts the wave to another framerate, with band-limited (windowed-sinc) interpolation.
+    ///
+    /// # Arguments
+    /// * `framerate` - The new number of samples per second.
+    ///
+    /// # Returns
+    /// * `Result<Wave<T>>` - The resampled wave, covering the same time span,
+    ///   or an error for a zero framerate.
+    pub fn resample(&self, framerate: u64) -> Result<Wave<T>> {
+        self.resample_with(framerate, Interpolation::default())
+    }
+
+    /// Converts the wave to another framerate with the given interpolation.
+    ///
+    /// The new samples start at the same time as the old ones and follow
+    /// the stored times of the wave, see [`TimeAxis::index`]. Beyond either
+    /// end the edge sample is held, see [`Extension::Hold`], so a constant
+    /// stays constant up to the last sample.
+    ///
+    /// # Arguments
+    /// * `framerate` - The new number of samples per second.
+    /// * `interpolation` - How to interpolate between the samples.
+    ///
+    /// # Returns
+    /// * `Result<Wave<T>>` - The resampled wave, covering the same time span,
+    ///   or an error for a zero framerate or a sinc without zero crossings.
+    pub fn resample_with(&self, framerate: u64, interpolation: Interpolation) -> Result<Wave<T>> {
+        check_framerate(framerate)?;
+        interpolation.check()?;
+        let start = self.time.start();
+        let ratio = self.framerate as f64 / framerate as f64;
+        let n = (self.ys.len() as f64 / ratio).round() as usize;
+
+        let ys = (0..n)
+            .map(|j| {
+                let x = match &self.time {
+                    TimeAxis::Uniform { .. } => j as f64 * ratio,
+                    time => time.index(start + j as f64 / framerate as f64, self.framerate),
+                };
+                T::from_f64(interpolation.interpolate(&self.ys, x, ratio, Extension::Hold))
+            })
+            .collect();
+        Ok(Wave {
+            ys,
+            time: TimeAxis::Uniform { start },
+            framerate,
+        })
+    }
+
+    /// Quantizes the wave to a signed integer bit depth, rounding to the nearest level.
+    ///
+    /// # Arguments
+    /// * `bits` - The bit depth, e.g. 8 or 16.
+    ///
+    /// # Returns
+    /// * `Result<Wave<T>>` - The quantized wave, still in full scale `[-1, 1)`,
+    ///   or an error for a bit depth outside of `1..=32`.
+    pub fn quantize(&self, bits: u32) -> Result<Wave<T>> {
+        self.quantize_with(bits, Rounding::Nearest, Dither::None)
+    }
+
+    /// Quantizes the wave to a signed integer bit depth.
+    ///
+    /// The full scale `[-1, 1)` is split into `2^bits` levels, a step of
+    /// `2^(1 - bits)`, and samples beyond it are clipped. The dither noise is
+    /// pseudo-random but seeded, so the result is reproducible.
+    ///
+    /// # Arguments
+    /// * `bits` - The bit depth, between 1 and 32.
+    /// * `rounding` - How to pick the level of each sample.
+    /// * `dither` - Noise added before rounding to decorrelate the error.
+    ///
+    /// # Returns
+    /// * `Result<Wave<T>>` - The quantized wave, still in full scale, or an
+    ///   error for a bit depth outside of `1..=32`.
+    pub fn quantize_with(&self, bits: u32, rounding: Rounding, dither: Dither) -> Result<Wave<T>> {
+        check_range("bits", bits, 1..=32)?;
+        let levels = (1u64 << (bits - 1)) as f64;
+        let step = 1.0 / levels;
+        let mut rng = Rng::new(DITHER_SEED);
+        let mut error = 0.0;
+
+        let ys = self
+            .ys
+            .iter()
+            .map(|&y| {
+                let target = match dither {
+                    Dither::NoiseShaped => y.into_f64() - error,
+                    Dither::None | Dither::Tpdf => y.into_f64(),
+                };
+                let noise = match dither {
+                    Dither::None => 0.0,
+                    Dither::Tpdf | Dither::NoiseShaped => (rng.next_f64() - rng.next_f64()) * step,
+                };
+                let level = rounding
+                    .round((target + noise) * levels)
+                    .clamp(-levels, levels - 1.0);
+                let quantized = level * step;
+                error = quantized - target;
+                T::from_f64(quantized)
+            })
+            .collect();
+        Ok(self.with_ys(ys))
+    }
+
+    /// Signal-to-noise ratio of the wave against a reference, e.g. the wave before quantizing.
+    ///
+    /// # Arguments
+    /// * `reference` - The clean wave, with the same number of samples.
+    ///
+    /// # Returns
+    /// * `Result<f64>` - The power of the reference over the power of the
+    ///   difference, in dB, infinity when the waves are the same, or an error
+    ///   if the lengths differ or the waves are empty.
+    pub fn snr(&self, reference: &Wave<T>) -> Result<f64> {
+        check_len(reference.len(), self.len())?;
+        if self.is_empty() {
+            return Err(Error::OutOfRange {
+                name: "samples",
+                value: 0.0,
+            });
+        }
+        let (signal, noise) =
+            reference
+                .ys
+                .iter()
+                .zip(&self.ys)
+                .fold((0.0, 0.0), |(signal, noise), (&r, &y)| {
+                    let (r, y) = (r.into_f64(), y.into_f64());
+                    (signal + r * r, noise + (y - r) * (y - r))
+                });
+        if noise == 0.0 {
+            return Ok(f64::INFINITY);
+        }
+        Ok(10.0 * (signal / noise).log10())
+    }
+
+    /// Multiplies the wave by an envelope, evaluated at the times of the samples.
+    ///
+    /// # Arguments
+    /// * `envelope` - The envelope; its times are absolute, like the wave's.
+    pub fn apply_envelope(&mut self, envelope: &Envelope<T>) {
+        let levels = envelope.evaluate(&self.ts());
+        for (y, e) in self.ys.iter_mut().zip(levels) {
+            *y *= e;
+        }
+    }
+
+    /// Tapers both ends of the wave linearly, so it starts and ends at zero without clicks.
+    ///
+    /// # Arguments
+    /// * `denom` - At most `1 / denom` of the wave is tapered at each end (the book uses 20).
+    /// * `duration` - At most this many seconds are tapered at each end (the book uses 0.1).
+    ///
+    /// The ramps never overlap: at most half of the wave is tapered at each end.
+    pub fn apodize(&mut self, denom: usize, duration: f64) {
+        let n = self.ys.len();
+        let k = (n / denom.max(1))
+            .min((duration * self.framerate as f64) as usize)
+            .min(n / 2);
+        if k == 0 {
+            return;
+        }
+        // Same ramp as the book's `np.linspace(0, 1, k)`.
+        let ramp = |i: usize| {
+            T::from_f64(if k == 1 {
+                0.0
+            } else {
+                i as f64 / (k - 1) as f64
+            })
+        };
+        for i in 0..k {
+            self.ys[i] *= ramp(i);
+            self.ys[n - 1 - i] *= ramp(i);
+        }
+    }
+
+    /// Returns the length of the wave.
+    ///
+    /// # Returns
+    /// * `usize` - Length of samples array.
+    pub fn len(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// Returns `true` if the wave has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.ys.is_empty()
+    }
+
+    /// Returns the duration of the wave.
+    ///
+    /// # Returns
+    /// * `f64` - The number of samples over the framerate, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.ys.len() as f64 / self.framerate as f64
+    }
+
+    /// Returns the time of the first sample, 0 for an empty wave.
+    pub fn start(&self) -> f64 {
+        self.time.start()
+    }
+
+    /// Returns the time of the last sample, the start for an empty wave.
+    pub fn end(&self) -> f64 {
+        match self.ys.len() {
+            0 => self.start(),
+            n => self.time.time(n - 1, self.framerate),
+        }
+    }
+
+    /// Returns the samples of the wave.
+    pub fn ys(&self) -> &[T] {
+        &self.ys
+    }
+
+    /// Returns the samples of the wave, to modify them in place.
+    pub fn ys_mut(&mut self) -> &mut [T] {
+        &mut self.ys
+    }
+
+    /// Returns the times of the samples of the wave, computed for evenly spaced ones.
+    pub fn ts(&self) -> Cow<'_, [f64]> {
+        self.time.times(self.ys.len(), self.framerate)
+    }
+
+    /// Returns the time axis of the wave.
+    pub fn time_axis(&self) -> &TimeAxis {
+        &self.time
+    }
+
+    /// Iterates over the samples with their times.
+    ///
+    /// # Returns
+    /// * `WaveIter<T>` - Pairs of time in seconds and sample.
+    pub fn iter(&self) -> WaveIter<'_, T> {
+        WaveIter {
+            wave: self,
+            index: 0,
+        }
+    }
+
+    /// Returns the index of the sample closest to a time.
+    ///
+    /// Evenly spaced samples are looked up on their grid, the others by bisection.
+    ///
+    /// # Arguments
+    /// * `t` - The time in seconds; times outside of the wave give its first
+    ///   or last sample.
+    ///
+    /// # Returns
+    /// * `Option<usize>` - The index, or `None` for an empty wave or a time
+    ///   that isn't finite.
+    pub fn find_index(&self, t: f64) -> Option<usize> {
+        let last = self.ys.len().checked_sub(1)?;
+        if !t.is_finite() {
+            return None;
+        }
+        Some(match &self.time {
+            TimeAxis::Uniform { start } => {
+                let i = ((t - start) * self.framerate as f64).round();
+                i.clamp(0.0, last as f64) as usize
+            }
+            TimeAxis::Explicit(ts) => match ts.partition_point(|&s| s < t) {
+                0 => 0,
+                i if i > last => last,
+                // Halfway between two samples, the later one, as on the grid.
+                i if t - ts[i - 1] < ts[i] - t => i - 1,
+                i => i,
+            },
+        })
+    }
+
+    /// Returns the largest difference between the samples of two waves.
+    ///
+    /// # Arguments
+    /// * `other` - The wave to compare with.
+    ///
+    /// # Returns
+    /// * `Result<f64>` - The largest absolute difference, NaN if any
+    ///   difference is NaN, or an error if the waves differ in length.
+    pub fn max_diff(&self, other: &Wave<T>) -> Result<f64> {
+        check_len(self.len(), other.len())?;
+        Ok(self
+            .ys
+            .iter()
+            .zip(&other.ys)
+            .map(|(&a, &b)| (a.into_f64() - b.into_f64()).abs())
+            // Unlike `f64::max`, a NaN sticks, as a mismatch does in `assert_close`.
+            .fold(0.0, |max, diff| {
+                if diff > max || diff.is_nan() {
+                    diff
+                } else {
+                    max
+                }
+            }))
+    }
+
+    /// Asserts that the samples of two waves are within `tolerance` of each other.
+    ///
+    /// # Arguments
+    /// * `expected` - The wave to compare with.
+    /// * `tolerance` - The largest absolute difference allowed.
+    ///
+    /// # Panics
+    /// If the waves differ in length, or at the first sample out of
+    /// tolerance, reporting its index, time and both values.
+    #[track_caller]
+    pub fn assert_close(&self, expected: &Wave<T>, tolerance: f64) {
+        assert_eq!(
+            self.len(),
+            expected.len(),
+            "waves differ in length: {} and {} samples",
+            self.len(),
+            expected.len()
+        );
+        let mismatch = self.ys.iter().zip(&expected.ys).position(|(&a, &b)| {
+            let diff = (a.into_f64() - b.into_f64()).abs();
+            diff > tolerance || diff.is_nan()
+        });
+        if let Some(i) = mismatch {
+            panic!(
+                "waves differ at sample {i} (t = {} s): {:?} and {:?}, tolerance {tolerance}",
+                self.time.time(i, self.framerate),
+                self.ys[i],
+                expected.ys[i]
+            );
+        }
+    }
+
+    /// Returns a wave with new samples at the times of this one.
+    fn with_ys(&self, ys: Vec<T>) -> Wave<T> {
+        Wave {
+            ys,
+            time: self.time.clone(),
+            framerate: self.framerate,
+        }
+    }
+}
+
+impl<T> Index<usize> for Wave<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.ys[i]
+    }
+}
+
+impl<T> IndexMut<usize> for Wave<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.ys[i]
+    }
+}
+
+impl<T: Sample> FromIterator<T> for Wave<T> {
+    /// Collects samples into a wave starting at 0, at [`DEFAULT_FRAMERATE`].
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Wave {
+            ys: iter.into_iter().collect(),
+            time: TimeAxis::Uniform { start: 0.0 },
+            framerate: DEFAULT_FRAMERATE,
+        }
+    }
+}
+
+impl<T: Sample> Extend<T> for Wave<T> {
+    /// Appends samples, one period of the framerate apart from the last one.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let n = self.ys.len();
+        self.ys.extend(iter);
+        if let TimeAxis::Explicit(ts) = &mut self.time {
+            let last = ts[n - 1];
+            ts.extend((1..=self.ys.len() - n).map(|k| last + k as f64 / self.framerate as f64));
+        }
+    }
+}
+
+impl<'a, T: Sample> IntoIterator for &'a Wave<T> {
+    type Item = (f64, T);
+    type IntoIter = WaveIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the samples of a wave with their times, see [`Wave::iter`].
+#[derive(Debug, Clone)]
+pub struct WaveIter<'a, T> {
+    wave: &'a Wave<T>,
+    index: usize,
+}
+
+impl<T: Sample> Iterator for WaveIter<'_, T> {
+    type Item = (f64, T);
+
+    fn next(&mut self) -> Option<(f64, T)> {
+        let y = *self.wave.ys.get(self.index)?;
+        let t = self.wave.time.time(self.index, self.wave.framerate);
+        self.index += 1;
+        Some((t, y))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.wave.ys.len() - self.index;
+        (n, Some(n))
+    }
+}
+
+impl<T: Sample> ExactSizeIterator for WaveIter<'_, T> {}
+
+impl<T: Sample> Signal<T> for Wave<T> {
+    /// Duration of the wave, so plots show all of it.
+    ///
+    /// # Returns
+    /// * `f64` - The duration in seconds.
+    fn period(&self) -> f64 {
+        self.duration()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        Periodicity::Aperiodic
+    }
+
+    /// Evaluates the wave at the given times, with linear interpolation
+    /// between the samples and zero outside of them, see [`Wave::interpolated`].
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        interpolate_wave(self, ts, Interpolation::Linear, Extension::Zero)
+    }
+}
+
+impl_signal_ops!([T: Sample,] Wave<T>);
+
+/// Represents a wave evaluated at any time, see [`Wave::interpolated`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpolated<T = f32> {
+    wave: Wave<T>,
+    interpolation: Interpolation,
+    extension: Extension,
+}
+
+impl<T: Sample> Interpolated<T> {
+    /// Returns the wave being interpolated.
+    pub fn wave(&self) -> &Wave<T> {
+        &self.wave
+    }
+}
+
+impl<T: Sample> Signal<T> for Interpolated<T> {
+    /// Duration of the wave, which is the period when it repeats.
+    ///
+    /// # Returns
+    /// * `f64` - The duration in seconds.
+    fn period(&self) -> f64 {
+        self.wave.period()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        match self.extension {
+            Extension::Periodic if !self.wave.is_empty() => Periodicity::Periodic(self.period()),
+            Extension::Periodic => Periodicity::Periodic(f64::INFINITY),
+            Extension::Zero | Extension::Hold => Periodicity::Aperiodic,
+        }
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        interpolate_wave(&self.wave, ts, self.interpolation, self.extension)
+    }
+}
+
+impl_signal_ops!([T: Sample,] Interpolated<T>);
+
+/// Evaluates a wave between its samples, at their fractional index, see [`TimeAxis::index`].
+///
+/// Between irregularly spaced samples, the interpolation runs over the
+/// index: exact for [`Interpolation::Nearest`] and [`Interpolation::Linear`],
+/// an approximation for the others, which assume even spacing.
+fn interpolate_wave<T: Sample>(
+    wave: &Wave<T>,
+    ts: &[f64],
+    interpolation: Interpolation,
+    extension: Extension,
+) -> Vec<T> {
+    ts.iter()
+        .map(|&t| {
+            let x = wave.time.index(t, wave.framerate);
+            T::from_f64(interpolation.interpolate(&wave.ys, x, 1.0, extension))
+        })
+        .collect()
+}
+
+/// How to compute a wave between its samples, see [`Wave::resample_with`] and [`Interpolated`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// The closest sample: a staircase.
+    Nearest,
+    /// Straight lines between neighbouring samples: cheap, but it aliases
+    /// when downsampling and dulls the highs when upsampling.
+    Linear,
+    /// Catmull-Rom cubic through the four closest samples: smooth, and exact
+    /// at the samples.
+    Cubic,
+    /// Sinc interpolation with a Blackman window, low-passed below the lower
+    /// of both Nyquist frequencies so downsampling doesn't alias.
+    Sinc {
+        /// Zero crossings of the sinc on each side of a sample.
+        half_width: usize,
+    },
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Interpolation::Sinc { half_width: 16 }
+    }
+}
+
+impl Interpolation {
+    /// Checks that a sinc has at least one zero crossing on each side.
+    fn check(self) -> Result<()> {
+        match self {
+            Interpolation::Sinc { half_width } => {
+                check_range("half_width", half_width as f64, 1.0..)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Value between the samples at fractional index `x`.
+    ///
+    /// `ratio` is the old framerate over the new one, which sets the cutoff of the sinc.
+    fn interpolate<T: Sample>(self, ys: &[T], x: f64, ratio: f64, extension: Extension) -> f64 {
+        let sample = |i: i64| extension.sample(ys, i);
+        match self {
+            Interpolation::Nearest => sample(x.round() as i64),
+            Interpolation::Linear => {
+                let i = x.floor();
+                let (a, b) = (sample(i as i64), sample(i as i64 + 1));
+                a + (b - a) * (x - i)
+            }
+            Interpolation::Cubic => {
+                let i = x.floor();
+                let f = x - i;
+                let i = i as i64;
+                let (p0, p1, p2, p3) = (sample(i - 1), sample(i), sample(i + 1), sample(i + 2));
+                p1 + 0.5
+                    * f
+                    * (p2 - p0
+                        + f * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3
+                            + f * (3.0 * (p1 - p2) + p3 - p0)))
+            }
+            Interpolation::Sinc { half_width } => {
+                // Below 1 when downsampling: the cutoff is the new Nyquist frequency.
+                let cutoff = (1.0 / ratio).min(1.0);
+                let reach = half_width as f64 / cutoff;
+                let first = (x - reach).ceil() as i64;
+                let last = (x + reach).floor() as i64;
+                (first..=last)
+                    .map(|i| {
+                        let d = x - i as f64;
+                        let u = d / reach;
+                        let window =
+                            0.42 + 0.5 * (std::f64::consts::PI * u).cos() + 0.08 * (TAU * u).cos();
+                        sample(i) * cutoff * sinc(cutoff * d) * window
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+/// What a wave is taken to be outside of its samples, see [`Interpolated`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Extension {
+    /// Silence before and after the wave.
+    #[default]
+    Zero,
+    /// The wave repeats forever, e.g. one cycle of a waveform.
+    Periodic,
+    /// The first sample carries on before the wave and the last one after it.
+    Hold,
+}
+
+impl Extension {
+    /// Sample `i` of `ys`, extended outside of it.
+    fn sample<T: Sample>(self, ys: &[T], i: i64) -> f64 {
+        let i = match self {
+            Extension::Zero => usize::try_from(i).ok(),
+            Extension::Periodic | Extension::Hold if ys.is_empty() => None,
+            Extension::Periodic => Some(i.rem_euclid(ys.len() as i64) as usize),
+            Extension::Hold => Some(i.clamp(0, ys.len() as i64 - 1) as usize),
+        };
+        i.and_then(|i| ys.get(i)).map_or(0.0, |y| y.into_f64())
+    }
+}
+
+/// Normalized sinc, `sin(πx) / πx`.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = std::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+/// How [`Wave::quantize_with`] picks the level of a sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// The nearest level, halfway cases away from zero.
+    #[default]
+    Nearest,
+    /// The level below, as when dropping the low bits of two's complement.
+    Floor,
+    /// The level towards zero, as when dropping the low bits of sign and magnitude.
+    TowardZero,
+}
+
+impl Rounding {
+    fn round(self, x: f64) -> f64 {
+        match self {
+            Rounding::Nearest => x.round(),
+            Rounding::Floor => x.floor(),
+            Rounding::TowardZero => x.trunc(),
+        }
+    }
+}
+
+/// Noise added by [`Wave::quantize_with`] before rounding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// No dither: the error follows the signal, which sounds like distortion.
+    #[default]
+    None,
+    /// Triangular noise spanning two steps, which makes the error independent
+    /// of the signal at the cost of about 4.8 dB of SNR.
+    Tpdf,
+    /// TPDF dither with first-order error feedback, which moves the noise
+    /// towards high frequencies, where it is less audible.
+    NoiseShaped,
+}
+
+/// Theoretical SNR of a full-scale sine quantized to the given bit depth, `6.02 bits + 1.76` dB.
+///
+/// # Arguments
+/// * `bits` - The bit depth.
+///
+/// # Returns
+/// * `f64` - The SNR in dB.
+pub fn quantization_snr(bits: u32) -> f64 {
+    20.0 * 2.0f64.log10() * bits as f64 + 10.0 * 1.5f64.log10()
+}
+
+/// Seed of the dither noise, fixed so quantizing is reproducible.
+const DITHER_SEED: u64 = 0x5EED_D17E;
+
+/// Small pseudo-random generator (SplitMix64) for dither noise.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Number of samples a [`Stream`] evaluates at once when used as an iterator.
+const STREAM_BLOCK: usize = 1024;
+
+/// Lazily generated samples of a signal, see [`Signal::stream`].
+///
+/// The time of every sample is computed from its index since `start`, so
+/// blocks line up without phase jumps and hours of audio can be rendered in
+/// constant memory. Samples can be pulled with [`Stream::fill`] or as an
+/// (endless) iterator, and both can be mixed.
+pub struct Stream<S, T = f32> {
+    signal: S,
+    start: f64,
+    framerate: u64,
+    /// Index of the next sample to evaluate.
+    index: u64,
+    /// Scratch buffer for the times of a block.
+    ts: Vec<f64>,
+    /// Samples evaluated for the iterator but not consumed yet.
+    buffer: Vec<T>,
+    pos: usize,
+}
+
+impl<T: Sample, S: Signal<T>> Stream<S, T> {
+    /// Creates a stream.
+    ///
+    /// # Arguments
+    /// * `signal` - The signal to sample, owned or borrowed.
+    /// * `start` - The time of the first sample in seconds.
+    /// * `framerate` - The number of frames per second.
+    ///
+    /// # Returns
+    /// * `Result<Stream<S, T>>` - The stream of samples, or an error for a
+    ///   zero framerate or a start time that isn't finite.
+    pub fn new(signal: S, start: f64, framerate: u64) -> Result<Self> {
+        check_framerate(framerate)?;
+        check_time(start)?;
+        Ok(Self {
+            signal,
+            start,
+            framerate,
+            index: 0,
+            ts: Vec::new(),
+            buffer: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// Returns the time of the next sample.
+    ///
+    /// # Returns
+    /// * `f64` - The time in seconds.
+    pub fn time(&self) -> f64 {
+        let buffered = (self.buffer.len() - self.pos) as u64;
+        self.time_at(self.index - buffered)
+    }
+
+    /// Fills a block with the next samples of the signal.
+    ///
+    /// # Arguments
+    /// * `block` - Where to write the samples; its length is the block size.
+    pub fn fill(&mut self, block: &mut [T]) {
+        let buffered = (self.buffer.len() - self.pos).min(block.len());
+        block[..buffered].copy_from_slice(&self.buffer[self.pos..self.pos + buffered]);
+        self.pos += buffered;
+        self.evaluate_block(&mut block[buffered..]);
+    }
+
+    /// Evaluates the next `out.len()` samples, past the buffered ones.
+    fn evaluate_block(&mut self, out: &mut [T]) {
+        if out.is_empty() {
+            return;
+        }
+        let (start, framerate) = (self.start, self.framerate as f64);
+        let first = self.index;
+        self.ts.clear();
+        self.ts
+            .extend((first..first + out.len() as u64).map(|i| start + i as f64 / framerate));
+        self.signal.evaluate_into(&self.ts, out);
+        self.index += out.len() as u64;
+    }
+
+    /// Time of the sample with the given index, as in [`Signal::make_wave`].
+    fn time_at(&self, index: u64) -> f64 {
+        self.start + index as f64 / self.framerate as f64
+    }
+}
+
+impl<T: Sample, S: Signal<T>> Iterator for Stream<S, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.pos == self.buffer.len() {
+            let mut buffer = std::mem::take(&mut self.buffer);
+            buffer.resize(STREAM_BLOCK, T::zero());
+            self.evaluate_block(&mut buffer);
+            self.buffer = buffer;
+            self.pos = 0;
+        }
+        self.pos += 1;
+        Some(self.buffer[self.pos - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Represents a discrete-time waveform with complex samples.
+///
+/// `T` is the type of the real and imaginary parts, see [`Sample`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexWave<T = f32> {
+    ys: Vec<Complex<T>>,
+    time: TimeAxis,
+    pub framerate: u64,
+}
+
+impl<T: Sample> ComplexWave<T> {
+    /// Creates a complex wave.
+    ///
+    /// # Arguments
+    /// * `ys` - wave array
+    /// * `ts` - array of times
+    /// * `framerate` - samples per second
+    ///
+    /// Evenly spaced times aren't kept, see [`TimeAxis::from_times`].
+    ///
+    /// # Returns
+    /// * `Result<ComplexWave>` - The generated wave, or an error if `ys` and
+    ///   `ts` differ in length, a time isn't finite or is before the previous
+    ///   one, or the framerate is zero.
+    pub fn new(ys: Vec<Complex<T>>, ts: Vec<f64>, framerate: u64) -> Result<Self> {
+        check_len(ts.len(), ys.len())?;
+        check_times(&ts)?;
+        check_framerate(framerate)?;
+        let time = TimeAxis::from_times(ts, framerate);
+        Ok(Self {
+            ys,
+            time,
+            framerate,
+        })
+    }
+
+    /// Plots the real and imaginary parts of the wave.
+    ///
+    /// The backend is picked from the environment, see [`Backend::from_env`].
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot(&self) -> Result<()> {
+        self.plot_with(Backend::from_env())
+    }
+
+    /// Plots the real and imaginary parts of the wave with the given backend.
+    ///
+    /// # Arguments
+    /// * `backend` - Where to draw the plot.
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot_with(&self, backend: Backend) -> Result<()> {
+        let (real, imag) = (self.real(), self.imag());
+        let series = [real.series("real"), imag.series("imag")];
+        render::render(backend, &series)
+    }
+
+    /// Returns the real part of the wave.
+    ///
+    /// # Returns
+    /// * `Wave<T>` - The real parts of the samples.
+    pub fn real(&self) -> Wave<T> {
+        self.map(|y| y.re)
+    }
+
+    /// Returns the imaginary part of the wave.
+    ///
+    /// # Returns
+    /// * `Wave<T>` - The imaginary parts of the samples.
+    pub fn imag(&self) -> Wave<T> {
+        self.map(|y| y.im)
+    }
+
+    /// Returns the magnitude of the wave, i.e. its envelope for an analytic signal.
+    ///
+    /// # Returns
+    /// * `Wave<T>` - The absolute values of the samples.
+    pub fn abs(&self) -> Wave<T> {
+        self.map(|y| y.norm())
+    }
+
+    /// Returns the phase of the wave.
+    ///
+    /// # Returns
+    /// * `Wave<T>` - The arguments of the samples in radians, in `(-π, π]`.
+    pub fn angle(&self) -> Wave<T> {
+        self.map(|y| y.arg())
+    }
+
+    /// Returns the complex conjugate of the wave.
+    ///
+    /// # Returns
+    /// * `ComplexWave<T>` - The wave with the imaginary parts negated.
+    pub fn conj(&self) -> ComplexWave<T> {
+        ComplexWave {
+            ys: self.ys.iter().map(|y| y.conj()).collect(),
+            time: self.time.clone(),
+            framerate: self.framerate,
+        }
+    }
+
+    /// Returns the length of the wave.
+    ///
+    /// # Returns
+    /// * `usize` - Length of samples array.
+    pub fn len(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// Returns `true` if the wave has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.ys.is_empty()
+    }
+
+    /// Returns the samples of the wave.
+    pub fn ys(&self) -> &[Complex<T>] {
+        &self.ys
+    }
+
+    /// Returns the times of the samples of the wave, computed for evenly spaced ones.
+    pub fn ts(&self) -> Cow<'_, [f64]> {
+        self.time.times(self.ys.len(), self.framerate)
+    }
+
+    /// Returns the time axis of the wave.
+    pub fn time_axis(&self) -> &TimeAxis {
+        &self.time
+    }
+
+    /// Applies `f` to every sample, keeping the times.
+    fn map(&self, f: impl Fn(Complex<T>) -> T) -> Wave<T> {
+        Wave {
+            ys: self.ys.iter().copied().map(f).collect(),
+            time: self.time.clone(),
+            framerate: self.framerate,
+        }
+    }
+}
+
+impl<T: Sample> From<&Wave<T>> for ComplexWave<T> {
+    /// Uses the samples of the wave as the real parts.
+    fn from(wave: &Wave<T>) -> Self {
+        Self {
+            ys: wave
+                .ys
+                .iter()
+                .map(|&y| Complex::new(y, T::zero()))
+                .collect(),
+            time: wave.time.clone(),
+            framerate: wave.framerate,
+        }
+    }
+}
+
+/// Represents a discrete-time waveform with several channels, e.g. stereo.
+///
+/// All the channels share the same times; for stereo, channel 0 is the left one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultichannelWave<T = f32> {
+    channels: Vec<Vec<T>>,
+    time: TimeAxis,
+    pub framerate: u64,
+}
+
+impl<T: Sample> MultichannelWave<T> {
+    /// Creates a multichannel wave.
+    ///
+    /// # Arguments
+    /// * `channels` - one wave array per channel, each as long as `ts`
+    /// * `ts` - array of times
+    /// * `framerate` - samples per second
+    ///
+    /// Evenly spaced times aren't kept, see [`TimeAxis::from_times`].
+    ///
+    /// # Returns
+    /// * `Result<MultichannelWave>` - The generated wave, or an error if there
+    ///   are no channels, a channel isn't as long as `ts`, a time isn't finite
+    ///   or is before the previous one, or the framerate is zero.
+    pub fn new(channels: Vec<Vec<T>>, ts: Vec<f64>, framerate: u64) -> Result<Self> {
+        for ys in &channels {
+            check_len(ts.len(), ys.len())?;
+        }
+        check_times(&ts)?;
+        check_framerate(framerate)?;
+        Self::with_time(channels, TimeAxis::from_times(ts, framerate), framerate)
+    }
+
+    /// Creates a multichannel wave from channels of the same length.
+    fn with_time(channels: Vec<Vec<T>>, time: TimeAxis, framerate: u64) -> Result<Self> {
+        let Some(first) = channels.first() else {
+            return Err(Error::OutOfRange {
+                name: "channels",
+                value: 0.0,
+            });
+        };
+        for ys in &channels {
+            check_len(first.len(), ys.len())?;
+        }
+        Ok(Self {
+            channels,
+            time,
+            framerate,
+        })
+    }
+
+    /// Creates a multichannel wave out of mono waves, one per channel.
+    ///
+    /// # Arguments
+    /// * `waves` - The channels; the times and framerate are the first one's.
+    ///
+    /// # Returns
+    /// * `Result<MultichannelWave>` - The generated wave, or an error if there
+    ///   are no waves, or they differ in length or framerate.
+    pub fn from_waves(waves: Vec<Wave<T>>) -> Result<Self> {
+        let Some(first) = waves.first() else {
+            return Err(Error::OutOfRange {
+                name: "channels",
+                value: 0.0,
+            });
+        };
+        let (time, framerate) = (first.time.clone(), first.framerate);
+        if let Some(w) = waves.iter().find(|w| w.framerate != framerate) {
+            return Err(Error::OutOfRange {
+                name: "framerate",
+                value: w.framerate as f64,
+            });
+        }
+        Self::with_time(waves.into_iter().map(|w| w.ys).collect(), time, framerate)
+    }
+
+    /// Creates a multichannel wave from interleaved samples, as stored in
+    /// WAV files: the first sample of every channel, then the second, etc.
+    ///
+    /// # Arguments
+    /// * `samples` - The interleaved samples.
+    /// * `channels` - The number of channels.
+    /// * `start` - The time of the first frame in seconds.
+    /// * `framerate` - The number of frames per second.
+    ///
+    /// # Returns
+    /// * `Result<MultichannelWave>` - The generated wave, or an error if there
+    ///   are no channels, the samples don't hold whole frames, the start time
+    ///   isn't finite or the framerate is zero.
+    pub fn from_interleaved(
+        samples: &[T],
+        channels: usize,
+        start: f64,
+        framerate: u64,
+    ) -> Result<Self> {
+        check_range("channels", channels as f64, 1.0..)?;
+        let n = samples.len() / channels;
+        check_len(n * channels, samples.len())?;
+        check_framerate(framerate)?;
+        check_time(start)?;
+        let channels = (0..channels)
+            .map(|c| samples.iter().skip(c).step_by(channels).copied().collect())
+            .collect();
+        Self::with_time(channels, TimeAxis::Uniform { start }, framerate)
+    }
+
+    /// Returns the samples interleaved, see [`MultichannelWave::from_interleaved`].
+    ///
+    /// # Returns
+    /// * `Vec<T>` - One frame after another.
+    pub fn interleave(&self) -> Vec<T> {
+        (0..self.len())
+            .flat_map(|i| self.channels.iter().map(move |ys| ys[i]))
+            .collect()
+    }
+
+    /// Returns the number of channels.
+    pub fn num_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns one channel as a mono wave.
+    ///
+    /// # Arguments
+    /// * `channel` - The index of the channel.
+    ///
+    /// # Returns
+    /// * `Wave<T>` - A copy of the channel.
+    pub fn channel(&self, channel: usize) -> Wave<T> {
+        Wave {
+            ys: self.channels[channel].clone(),
+            time: self.time.clone(),
+            framerate: self.framerate,
+        }
+    }
+
+    /// Returns the samples of one channel.
+    pub fn channel_ys(&self, channel: usize) -> &[T] {
+        &self.channels[channel]
+    }
+
+    /// Returns the samples of one channel, to modify them in place.
+    pub fn channel_ys_mut(&mut self, channel: usize) -> &mut [T] {
+        &mut self.channels[channel]
+    }
+
+    /// Splits the wave into mono waves, one per channel.
+    ///
+    /// # Returns
+    /// * `Vec<Wave<T>>` - The channels.
+    pub fn into_waves(self) -> Vec<Wave<T>> {
+        let (time, framerate) = (self.time, self.framerate);
+        self.channels
+            .into_iter()
+            .map(|ys| Wave {
+                ys,
+                time: time.clone(),
+                framerate,
+            })
+            .collect()
+    }
+
+    /// Mixes all the channels down to mono, with equal weights.
+    ///
+    /// # Returns
+    /// * `Wave<T>` - The average of the channels.
+    pub fn downmix(&self) -> Wave<T> {
+        let n = self.channels.len().max(1);
+        let gains = vec![T::from_f64(1.0 / n as f64); self.channels.len()];
+        self.mix(&gains)
+    }
+
+    /// Mixes all the channels down to mono.
+    ///
+    /// # Arguments
+    /// * `gains` - The weight of each channel.
+    ///
+    /// # Returns
+    /// * `Result<Wave<T>>` - The weighted sum of the channels, or an error if
+    ///   there isn't one gain per channel.
+    pub fn downmix_with(&self, gains: &[T]) -> Result<Wave<T>> {
+        check_len(self.channels.len(), gains.len())?;
+        Ok(self.mix(gains))
+    }
+
+    /// Weighted sum of the channels, with one gain per channel.
+    fn mix(&self, gains: &[T]) -> Wave<T> {
+        let mut ys = vec![T::zero(); self.len()];
+        for (channel, &gain) in self.channels.iter().zip(gains) {
+            for (y, &c) in ys.iter_mut().zip(channel) {
+                *y += gain * c;
+            }
+        }
+        Wave {
+            ys,
+            time: self.time.clone(),
+            framerate: self.framerate,
+        }
+    }
+
+    /// Plots every channel in its own plot, one below the other.
+    ///
+    /// The backend is picked from the environment, see [`Backend::from_env`].
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot(&self) -> Result<()> {
+        self.plot_with(Backend::from_env())
+    }
+
+    /// Plots every channel in its own plot with the given backend.
+    ///
+    /// # Arguments
+    /// * `backend` - Where to draw the plot.
+    ///
+    /// # Returns
+    /// * `Result<()>` - An error if the plot can't be drawn.
+    pub fn plot_with(&self, backend: Backend) -> Result<()> {
+        let names: Vec<String> = match self.channels.len() {
+            2 => vec!["left".to_string(), "right".to_string()],
+            n => (0..n).map(|c| format!("channel {c}")).collect(),
+        };
+        let times = self.time.plot_times(self.len(), self.framerate);
+        let series: Vec<Series<T>> = names
+            .iter()
+            .zip(&self.channels)
+            .map(|(name, ys)| Series::with_times(name, times, ys))
+            .collect();
+        render::render_channels(backend, &series)
+    }
+
+    /// Returns the number of frames, i.e. of samples in each channel.
+    ///
+    /// # Returns
+    /// * `usize` - Length of the channels.
+    pub fn len(&self) -> usize {
+        self.channels.first().map_or(0, Vec::len)
+    }
+
+    /// Returns `true` if the wave has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the times of the frames of the wave, computed for evenly spaced ones.
+    pub fn ts(&self) -> Cow<'_, [f64]> {
+        self.time.times(self.len(), self.framerate)
+    }
+
+    /// Returns the time axis of the wave.
+    pub fn time_axis(&self) -> &TimeAxis {
+        &self.time
+    }
+}
+
+/// Represents a mono signal placed in a stereo field, see [`Signal::pan`].
+///
+/// It uses the constant-power pan law: the gains are `cos θ` and `sin θ` for
+/// `θ` from 0 (left) to π/2 (right), so the loudness doesn't dip in the
+/// center, where each side gets `1/√2`.
+pub struct Panned<S, T = f32> {
+    signal: S,
+    position: T,
+}
+
+impl<T: Sample, S: Signal<T>> Panned<S, T> {
+    /// Creates a panned signal.
+    ///
+    /// # Arguments
+    /// * `signal` - The mono signal.
+    /// * `position` - From -1 (left) through 0 (center) to 1 (right); clamped.
+    ///
+    /// # Returns
+    /// * `Panned<S, T>` - The panned signal.
+    pub fn new(signal: S, position: T) -> Self {
+        let position = position.max(-T::one()).min(T::one());
+        Self { signal, position }
+    }
+
+    /// Returns the gains of the left and right channels.
+    ///
+    /// # Returns
+    /// * `(T, T)` - The left and right gains.
+    pub fn gains(&self) -> (T, T) {
+        let theta = (self.position + T::one()) * T::FRAC_PI_4();
+        (theta.cos(), theta.sin())
+    }
+
+    /// Creates a stereo wave from the signal.
+    ///
+    /// # Arguments
+    /// * `duration` - The duration of the wave in seconds.
+    /// * `start` - The start time in seconds.
+    /// * `framerate` - The number of frames per second.
+    ///
+    /// # Returns
+    /// * `Result<MultichannelWave<T>>` - The stereo wave, or an error for
+    ///   invalid arguments, see [`Signal::make_wave`].
+    pub fn make_wave(
+        &self,
+        duration: f64,
+        start: f64,
+        framerate: u64,
+    ) -> Result<MultichannelWave<T>> {
+        let mono = self.signal.make_wave(duration, start, framerate)?;
+        let (left, right) = self.gains();
+        let channels = [left, right]
+            .iter()
+            .map(|&gain| mono.ys.iter().map(|&y| y * gain).collect())
+            .collect();
+        MultichannelWave::with_time(channels, mono.time, framerate)
+    }
+}
+
+/// Represents a sinusoidal signal.
+pub struct Sinusoid<T = f32> {
+    freq: f64,
+    amp: T,
+    offset: T,
+    func: Box<dyn Fn(T) -> T + Send + Sync>,
+}
+
+impl<T: Sample> Sinusoid<T> {
+    /// Creates a sinusoidal signal.
+    ///
+    /// # Arguments
+    /// * `freq` - The frequency of the signal in Hz.
+    /// * `amp` - The amplitude of the signal.
+    /// * `offset` - The phase offset in radians.
+    /// * `func` - The function that maps phase to amplitude. It may be a closure
+    ///   capturing its own state, such as a duty cycle or a table of harmonics.
+    ///   The phase it gets is reduced to one cycle, `[0, 2π)`, plus `offset`.
+    ///   It must be `Send + Sync`, so the signal can be shared between threads.
+    ///
+    /// # Returns
+    /// * `Result<Sinusoid<T>>` - The generated sinusoidal signal, or an error
+    ///   if the frequency isn't positive and finite.
+    pub fn new(
+        freq: f64,
+        amp: T,
+        offset: T,
+        func: impl Fn(T) -> T + Send + Sync + 'static,
+    ) -> Result<Self> {
+        check_frequency(freq)?;
+        Ok(Sinusoid {
+            freq,
+            amp,
+            offset,
+            func: Box::new(func),
+        })
+    }
+
+    /// Creates a signal that repeats a single-cycle waveform.
+    ///
+    /// # Arguments
+    /// * `freq` - The frequency of the signal in Hz.
+    /// * `amp` - The amplitude of the signal.
+    /// * `offset` - The phase offset in radians.
+    /// * `wavetable` - One cycle of the waveform.
+    ///
+    /// # Returns
+    /// * `Result<Sinusoid<T>>` - The generated signal, or an error if the
+    ///   frequency isn't positive and finite.
+    pub fn from_wavetable(freq: f64, amp: T, offset: T, wavetable: Wavetable<T>) -> Result<Self> {
+        Sinusoid::new(freq, amp, offset, move |phase| wavetable.lookup(phase))
+    }
+}
+
+impl<T: Sample> Signal<T> for Sinusoid<T> {
+    fn period(&self) -> f64 {
+        1.0 / self.freq
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let mut ys = vec![T::zero(); ts.len()];
+        self.evaluate_into(ts, &mut ys);
+        ys
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        for (y, &t) in out.iter_mut().zip(ts) {
+            let phase = T::from_f64(TAU * cycles(self.freq, t)) + self.offset;
+            *y = self.amp * (self.func)(phase);
+        }
+    }
+}
+
+impl_signal_ops!([T: Sample,] Sinusoid<T>);
+
+/// Fraction of the current cycle of frequency `freq` at time `t`, in `[0, 1)`.
+///
+/// Only the position within the current cycle matters, and it has to be
+/// taken in f64: after a few minutes `freq * t` has too many whole cycles
+/// for f32 to keep the fraction.
+fn cycles(freq: f64, t: f64) -> f64 {
+    (freq * t).rem_euclid(1.0)
+}
+
+/// Represents one cycle of a waveform, looked up by phase with linear interpolation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wavetable<T = f32> {
+    table: Vec<T>,
+}
+
+impl<T: Sample> Wavetable<T> {
+    /// Creates a wavetable.
+    ///
+    /// # Arguments
+    /// * `table` - Samples of exactly one cycle, evenly spaced in phase,
+    ///   without repeating the first sample at the end.
+    ///
+    /// # Returns
+    /// * `Wavetable` - The generated wavetable.
+    pub fn new(table: Vec<T>) -> Self {
+        Self { table }
+    }
+
+    /// Returns the value of the waveform at the given phase.
+    ///
+    /// # Arguments
+    /// * `phase` - The phase in radians; any value is wrapped into one cycle.
+    ///
+    /// # Returns
+    /// * `T` - The interpolated value, 0 for an empty table.
+    pub fn lookup(&self, phase: T) -> T {
+        let n = self.table.len();
+        if n == 0 {
+            return T::zero();
+        }
+        let cycles = (phase.into_f64() / TAU).rem_euclid(1.0);
+        let position = cycles * n as f64;
+        let i = (position.floor() as usize).min(n - 1);
+        let frac = T::from_f64(position - i as f64);
+        let (a, b) = (self.table[i], self.table[(i + 1) % n]);
+        a + (b - a) * frac
+    }
+}
+
+impl<T: Sample> From<&Wave<T>> for Wavetable<T> {
+    /// Uses all the samples of the wave as one cycle.
+    fn from(wave: &Wave<T>) -> Self {
+        Self::new(wave.ys.clone())
+    }
+}
+
+/// Represents a cosine sinusoid.
+pub struct CosSignal<T = f32>(Sinusoid<T>);
+
+impl<T: Sample> CosSignal<T> {
+    /// Creates a cosine signal.
+    ///
+    /// # Arguments
+    /// * `freq` - The frequency of the signal in Hz.
+    /// * `amp` - The amplitude of the signal.
+    /// * `offset` - The phase offset in radians.
+    ///
+    /// # Returns
+    /// * `Result<CosSignal<T>>` - The generated cosine signal, or an error if
+    ///   the frequency isn't positive and finite.
+    pub fn new(freq: f64, amp: T, offset: T) -> Result<Self> {
+        Sinusoid::new(freq, amp, offset, T::cos).map(Self)
+    }
+}
+
+impl<T: Sample> Signal<T> for CosSignal<T> {
+    fn period(&self) -> f64 {
+        self.0.period()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        self.0.evaluate(ts)
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        self.0.evaluate_into(ts, out)
+    }
+}
+
+impl_signal_ops!([T: Sample,] CosSignal<T>);
+
+impl<T: Sample> From<CosSignal<T>> for Sinusoid<T> {
+    fn from(c: CosSignal<T>) -> Self {
+        c.0
+    }
+}
+
+/// Represents a sine sinusoid.
+pub struct SinSignal<T = f32>(Sinusoid<T>);
+
+impl<T: Sample> SinSignal<T> {
+    /// Creates a sine signal.
+    ///
+    /// # Arguments
+    /// * `freq` - The frequency of the signal in Hz.
+    /// * `amp` - The amplitude of the signal.
+    /// * `offset` - The phase offset in radians.
+    ///
+    /// # Returns
+    /// * `Result<SinSignal<T>>` - The generated sine signal, or an error if
+    ///   the frequency isn't positive and finite.
+    pub fn new(freq: f64, amp: T, offset: T) -> Result<Self> {
+        Sinusoid::new(freq, amp, offset, T::sin).map(Self)
+    }
+}
+
+impl<T: Sample> Signal<T> for SinSignal<T> {
+    fn period(&self) -> f64 {
+        self.0.period()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        self.0.evaluate(ts)
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        self.0.evaluate_into(ts, out)
+    }
+}
+
+impl_signal_ops!([T: Sample,] SinSignal<T>);
+
+impl<T: Sample> From<SinSignal<T>> for Sinusoid<T> {
+    fn from(s: SinSignal<T>) -> Self {
+        s.0
+    }
+}
+
+/// Represents a sum of harmonics of a fundamental, built from a table:
+/// `Σ amp_n * sin(2π n freq t + phase_n)`.
+///
+/// Unlike nested [`SumSignal`]s, the period is exactly `1 / freq`, and a
+/// cosine is a sine with phase `π/2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicSignal<T = f32> {
+    freq: f64,
+    /// `(harmonic number, amplitude, phase)` of each component.
+    harmonics: Vec<(u32, T, T)>,
+}
+
+impl<T: Sample> HarmonicSignal<T> {
+    /// Creates a signal from a table of harmonics.
+    ///
+    /// # Arguments
+    /// * `freq` - The fundamental frequency in Hz.
+    /// * `harmonics` - `(harmonic number, amplitude, phase)` of each component;
+    ///   harmonic 1 is the fundamental and harmonic 0 a constant offset.
+    ///
+    /// # Returns
+    /// * `Result<HarmonicSignal<T>>` - The generated signal, or an error if
+    ///   the fundamental isn't positive and finite.
+    pub fn new(freq: f64, harmonics: Vec<(u32, T, T)>) -> Result<Self> {
+        check_frequency(freq)?;
+        Ok(Self { freq, harmonics })
+    }
+
+    /// Creates a signal from a function giving the amplitude of each harmonic,
+    /// e.g. `|n| 1.0 / n as f32` for a sawtooth.
+    ///
+    /// # Arguments
+    /// * `freq` - The fundamental frequency in Hz.
+    /// * `count` - The number of harmonics, starting at the fundamental.
+    /// * `amp` - The amplitude of harmonic `n`; all the phases are 0.
+    ///
+    /// # Returns
+    /// * `Result<HarmonicSignal<T>>` - The generated signal, or an error if
+    ///   the fundamental isn't positive and finite.
+    pub fn from_fn(freq: f64, count: u32, amp: impl Fn(u32) -> T) -> Result<Self> {
+        let harmonics = (1..=count).map(|n| (n, amp(n), T::zero())).collect();
+        Self::new(freq, harmonics)
+    }
+
+    /// Returns the number of harmonics.
+    ///
+    /// # Returns
+    /// * `usize` - Number of entries in the table.
+    pub fn len(&self) -> usize {
+        self.harmonics.len()
+    }
+
+    /// Returns `true` if the signal has no harmonics.
+    pub fn is_empty(&self) -> bool {
+        self.harmonics.is_empty()
+    }
+}
+
+impl<T: Sample> Signal<T> for HarmonicSignal<T> {
+    fn period(&self) -> f64 {
+        1.0 / self.freq
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        ts.iter()
+            .map(|&t| {
+                // Reduced once for the fundamental, then again for each harmonic.
+                let fundamental = cycles(self.freq, t);
+                self.harmonics
+                    .iter()
+                    .map(|&(n, amp, phase)| {
+                        let cycles = cycles(n as f64, fundamental);
+                        amp * (T::from_f64(TAU * cycles) + phase).sin()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+impl_signal_ops!([T: Sample,] HarmonicSignal<T>);
+
+/// Represents a frequency-modulated sine, `amp * sin(2π freq t + index * sin(2π mod_freq t))`.
+///
+/// The phase is the integral of the instantaneous frequency
+/// `freq + index * mod_freq * cos(2π mod_freq t)`, so the carrier never jumps.
+/// More modulators can be stacked, each one modulating the previous.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FmSignal<T = f32> {
+    freq: f64,
+    amp: T,
+    /// `(frequency, index)` of each modulator, starting with the carrier's.
+    modulators: Vec<(f64, f64)>,
+}
+
+impl<T: Sample> FmSignal<T> {
+    /// Creates a frequency-modulated signal.
+    ///
+    /// # Arguments
+    /// * `freq` - The frequency of the carrier in Hz.
+    /// * `amp` - The amplitude of the signal.
+    /// * `mod_freq` - The frequency of the modulator in Hz.
+    /// * `index` - The modulation index: the peak phase deviation in radians.
+    ///
+    /// # Returns
+    /// * `Result<FmSignal<T>>` - The generated signal, or an error if a
+    ///   frequency isn't positive and finite or the index isn't finite.
+    pub fn new(freq: f64, amp: T, mod_freq: f64, index: f64) -> Result<Self> {
+        check_frequency(freq)?;
+        Self {
+            freq,
+            amp,
+            modulators: Vec::new(),
+        }
+        .stack(mod_freq, index)
+    }
+
+    /// Stacks another modulator, which modulates the last one added.
+    ///
+    /// # Arguments
+    /// * `mod_freq` - The frequency of the new modulator in Hz.
+    /// * `index` - Its modulation index in radians.
+    ///
+    /// # Returns
+    /// * `Result<FmSignal<T>>` - The signal with the modulator stacked, or an
+    ///   error if the frequency isn't positive and finite or the index isn't
+    ///   finite.
+    pub fn stack(mut self, mod_freq: f64, index: f64) -> Result<Self> {
+        check_frequency(mod_freq)?;
+        if !index.is_finite() {
+            return Err(Error::OutOfRange {
+                name: "index",
+                value: index,
+            });
+        }
+        self.modulators.push((mod_freq, index));
+        Ok(self)
+    }
+
+    /// Predicts the sidebands of the signal: `amp * J_k(index)` at `freq + k * mod_freq`.
+    ///
+    /// Only the modulator of the carrier is taken into account; with stacked
+    /// modulators the spectrum is richer than this.
+    ///
+    /// # Arguments
+    /// * `count` - The number of sidebands on each side of the carrier.
+    ///
+    /// # Returns
+    /// * `Vec<(f64, T)>` - Frequency and amplitude of each component, from
+    ///   the lowest. Frequencies below zero are kept as they are; they sound
+    ///   at the positive frequency with the sign flipped.
+    pub fn sidebands(&self, count: usize) -> Vec<(f64, T)> {
+        let (mod_freq, index) = self.modulators[0];
+        let count = count as i32;
+        (-count..=count)
+            .map(|k| {
+                let amp = self.amp * T::from_f64(bessel_j(k, index));
+                (self.freq + k as f64 * mod_freq, amp)
+            })
+            .collect()
+    }
+
+    /// Phase of the carrier at time `t`, in radians.
+    fn phase(&self, t: f64) -> f64 {
+        let mut modulation = 0.0;
+        for &(freq, index) in self.modulators.iter().rev() {
+            modulation = index * (TAU * cycles(freq, t) + modulation).sin();
+        }
+        TAU * cycles(self.freq, t) + modulation
+    }
+}
+
+impl<T: Sample> Signal<T> for FmSignal<T> {
+    /// Period of the signal, like [`SumSignal::period`].
+    ///
+    /// # Returns
+    /// * `f64` - The period of the signal in seconds.
+    fn period(&self) -> f64 {
+        period_or_fallback(self.periodicity(), || {
+            std::iter::once(self.freq)
+                .chain(self.modulators.iter().map(|&(freq, _)| freq))
+                .map(|freq| 1.0 / freq)
+        })
+    }
+
+    /// Period shared by the carrier and the modulators in use, see [`Periodicity::combine`].
+    ///
+    /// # Returns
+    /// * `Periodicity` - The common period, or `Aperiodic`.
+    fn periodicity(&self) -> Periodicity {
+        self.modulators
+            .iter()
+            .filter(|&&(_, index)| index != 0.0)
+            .map(|&(freq, _)| Periodicity::Periodic(1.0 / freq))
+            .fold(Periodicity::Periodic(1.0 / self.freq), Periodicity::combine)
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        ts.iter()
+            .map(|&t| self.amp * T::from_f64(self.phase(t).sin()))
+            .collect()
+    }
+}
+
+impl_signal_ops!([T: Sample,] FmSignal<T>);
+
+/// Bessel function of the first kind, `J_n(x)`, which gives the sidebands of FM.
+///
+/// Uses the trapezoidal rule on Bessel's integral
+/// `J_n(x) = 1/2π ∫ cos(n τ - x sin τ) dτ` over a whole period,
+/// which converges very fast for a periodic integrand.
+///
+/// # Arguments
+/// * `n` - The order.
+/// * `x` - The argument.
+///
+/// # Returns
+/// * `f64` - The value of the function.
+pub fn bessel_j(n: i32, x: f64) -> f64 {
+    let steps = 2 * (n.unsigned_abs() as usize + x.abs().ceil() as usize) + 64;
+    let sum: f64 = (0..steps)
+        .map(|i| {
+            let tau = TAU * i as f64 / steps as f64;
+            (n as f64 * tau - x * tau.sin()).cos()
+        })
+        .sum();
+    sum / steps as f64
+}
+
+/// Represents a complex sinusoid, `amp * e^{i(2π freq t + offset)}`.
+///
+/// It doesn't implement [`Signal`], whose samples are real; its real and
+/// imaginary parts do, see [`ComplexSinusoid::real`] and [`ComplexSinusoid::imag`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexSinusoid<T = f32> {
+    freq: f64,
+    amp: T,
+    offset: T,
+}
+
+impl<T: Sample> ComplexSinusoid<T> {
+    /// Creates a complex sinusoid.
+    ///
+    /// # Arguments
+    /// * `freq` - The frequency of the signal in Hz; negative frequencies turn clockwise.
+    /// * `amp` - The amplitude of the signal.
+    /// * `offset` - The phase offset in radians.
+    ///
+    /// # Returns
+    /// * `Result<ComplexSinusoid<T>>` - The generated complex sinusoid, or an
+    ///   error if the frequency is zero or not finite.
+    pub fn new(freq: f64, amp: T, offset: T) -> Result<Self> {
+        if !(freq.is_finite() && freq != 0.0) {
+            return Err(Error::InvalidFrequency(freq));
+        }
+        Ok(Self { freq, amp, offset })
+    }
+
+    /// Period of the signal.
+    ///
+    /// # Returns
+    /// * `f64` - The period of the signal in seconds.
+    pub fn period(&self) -> f64 {
+        1.0 / self.freq.abs()
+    }
+
+    /// Evaluates the signal at the given times.
+    ///
+    /// # Arguments
+    /// * `ts` - A vector of time points (in seconds) at which to evaluate the signal.
+    ///
+    /// # Returns
+    /// * `Vec<Complex<T>>` - The values of the signal at the given time points.
+    pub fn evaluate(&self, ts: &[f64]) -> Vec<Complex<T>> {
+        ts.iter()
+            .map(|&t| {
+                let phase = T::from_f64(TAU * cycles(self.freq, t)) + self.offset;
+                Complex::from_polar(self.amp, phase)
+            })
+            .collect()
+    }
+
+    /// Creates a complex wave from the signal.
+    ///
+    /// # Arguments
+    /// * `duration` - The duration of the wave in seconds.
+    /// * `start` - The start time in seconds.
+    /// * `framerate` - The number of frames per second.
+    ///
+    /// # Returns
+    /// * `Result<ComplexWave<T>>` - The generated wave, or an error for
+    ///   invalid arguments, see [`Signal::make_wave`].
+    pub fn make_wave(&self, duration: f64, start: f64, framerate: u64) -> Result<ComplexWave<T>> {
+        let ts = sample_times(duration, start, framerate)?;
+        let ys = self.evaluate(&ts);
+
+        Ok(ComplexWave {
+            ys,
+            time: TimeAxis::Uniform { start },
+            framerate,
+        })
+    }
+
+    /// Returns the real part, `amp * cos(2π freq t + offset)`.
+    ///
+    /// # Returns
+    /// * `CosSignal<T>` - The real part of the signal.
+    pub fn real(&self) -> CosSignal<T> {
+        // cos is even, so a clockwise sinusoid has the same real part as
+        // the counterclockwise one with the offset negated.
+        let (freq, offset) = self.positive();
+        CosSignal(Sinusoid {
+            freq,
+            amp: self.amp,
+            offset,
+            func: Box::new(T::cos),
+        })
+    }
+
+    /// Returns the imaginary part, `amp * sin(2π freq t + offset)`.
+    ///
+    /// # Returns
+    /// * `SinSignal<T>` - The imaginary part of the signal.
+    pub fn imag(&self) -> SinSignal<T> {
+        // sin is odd, so a clockwise sinusoid flips the sign of the amplitude.
+        let (freq, offset) = self.positive();
+        let amp = if self.freq < 0.0 { -self.amp } else { self.amp };
+        SinSignal(Sinusoid {
+            freq,
+            amp,
+            offset,
+            func: Box::new(T::sin),
+        })
+    }
+
+    /// Returns the complex conjugate, which turns the other way.
+    ///
+    /// # Returns
+    /// * `ComplexSinusoid<T>` - The sinusoid with frequency and offset negated.
+    pub fn conj(&self) -> Self {
+        Self {
+            freq: -self.freq,
+            amp: self.amp,
+            offset: -self.offset,
+        }
+    }
+
+    /// Frequency and offset of the sinusoid turning counterclockwise with
+    /// the same real part.
+    fn positive(&self) -> (f64, T) {
+        if self.freq < 0.0 {
+            (-self.freq, -self.offset)
+        } else {
+            (self.freq, self.offset)
+        }
+    }
+
+    /// Returns the magnitude of the signal, which is constant.
+    ///
+    /// # Returns
+    /// * `T` - The amplitude.
+    pub fn abs(&self) -> T {
+        self.amp
+    }
+
+    /// Returns the phase of the signal at the given times.
+    ///
+    /// # Arguments
+    /// * `ts` - The time points in seconds.
+    ///
+    /// # Returns
+    /// * `Vec<T>` - The phases in radians, in `(-π, π]`.
+    pub fn angle(&self, ts: &[f64]) -> Vec<T> {
+        self.evaluate(ts).into_iter().map(|y| y.arg()).collect()
+    }
+}
+
+/// Represents silence.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SilentSignal;
+
+impl<T: Sample> Signal<T> for SilentSignal {
+    /// Silence is constant, so it never changes the period of a sum.
+    ///
+    /// # Returns
+    /// * `Periodicity` - An infinite period.
+    fn periodicity(&self) -> Periodicity {
+        Periodicity::Periodic(f64::INFINITY)
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        vec![T::zero(); ts.len()]
+    }
+
+    fn evaluate_into(&self, _ts: &[f64], out: &mut [T]) {
+        out.fill(T::zero());
+    }
+}
+
+impl_signal_ops!(SilentSignal);
+
+/// Represents a train of impulses: zero, except at given times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impulses<T = f32> {
+    locations: Vec<f64>,
+    amps: Vec<T>,
+    /// Framerate the train is sampled at, which sets which impulses reach the first sample.
+    framerate: u64,
+}
+
+impl<T: Sample> Impulses<T> {
+    /// Creates an impulse train.
+    ///
+    /// # Arguments
+    /// * `locations` - The times of the impulses in seconds.
+    /// * `amps` - The amplitude of each impulse.
+    /// * `framerate` - The number of samples per second the train is evaluated at.
+    ///
+    /// # Returns
+    /// * `Result<Impulses<T>>` - The generated signal, or an error if there
+    ///   isn't one amplitude per location, a location isn't finite or the
+    ///   framerate is zero.
+    pub fn new(locations: Vec<f64>, amps: Vec<T>, framerate: u64) -> Result<Self> {
+        check_len(locations.len(), amps.len())?;
+        check_framerate(framerate)?;
+        for &t in &locations {
+            check_time(t)?;
+        }
+        Ok(Self {
+            locations,
+            amps,
+            framerate,
+        })
+    }
+
+    /// Creates an impulse train with the same amplitude for every impulse.
+    ///
+    /// # Arguments
+    /// * `locations` - The times of the impulses in seconds.
+    /// * `amp` - The amplitude of the impulses.
+    /// * `framerate` - The number of samples per second the train is evaluated at.
+    ///
+    /// # Returns
+    /// * `Result<Impulses<T>>` - The generated signal, or an error if a
+    ///   location isn't finite or the framerate is zero.
+    pub fn uniform(locations: Vec<f64>, amp: T, framerate: u64) -> Result<Self> {
+        let amps = vec![amp; locations.len()];
+        Self::new(locations, amps, framerate)
+    }
+}
+
+impl<T: Sample> Signal<T> for Impulses<T> {
+    fn periodicity(&self) -> Periodicity {
+        Periodicity::Aperiodic
+    }
+
+    /// Evaluates the signal at the given times, which must be sorted.
+    ///
+    /// Like the book's `np.searchsorted`, each impulse lands on the first
+    /// sample at or after its location, replacing any earlier impulse there.
+    ///
+    /// NOTE: unlike the book, an impulse more than one sample period
+    /// (`1 / framerate`) before `ts[0]` is dropped rather than moved to the
+    /// first sample, so evaluating in blocks of any size (see [`Stream`])
+    /// gives each impulse once.
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let mut ys = vec![T::zero(); ts.len()];
+        let spacing = 1.0 / self.framerate as f64;
+        for (&location, &amp) in self.locations.iter().zip(&self.amps) {
+            let i = ts.partition_point(|&t| t < location);
+            if i < ts.len() && (i > 0 || location > ts[0] - spacing || location == ts[0]) {
+                ys[i] = amp;
+            }
+        }
+        ys
+    }
+}
+
+impl_signal_ops!([T: Sample,] Impulses<T>);
+
+/// Represents an amplitude envelope: a piecewise-linear curve through breakpoints.
+///
+/// It is a signal itself, so `signal * envelope` shapes any signal, see
+/// [`Envelope::apply`]; [`Wave::apply_envelope`] shapes a wave.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope<T = f32> {
+    points: Vec<(f64, T)>,
+}
+
+impl<T: Sample> Envelope<T> {
+    /// Creates an envelope from breakpoints.
+    ///
+    /// # Arguments
+    /// * `points` - Pairs of time in seconds and level, in any order. Before
+    ///   the first breakpoint the level is the first one's, after the last the last one's.
+    ///
+    /// # Returns
+    /// * `Result<Envelope<T>>` - The generated envelope, or an error if a
+    ///   time isn't finite.
+    pub fn new(mut points: Vec<(f64, T)>) -> Result<Self> {
+        for &(t, _) in &points {
+            check_time(t)?;
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { points })
+    }
+
+    /// Creates an attack-decay-sustain-release envelope for a note starting at 0.
+    ///
+    /// The level rises from 0 to 1 during `attack`, falls to `sustain` during
+    /// `decay`, holds, then falls to 0 during `release`, ending at `duration`.
+    /// A note too short for its attack and decay is released right after them.
+    ///
+    /// # Arguments
+    /// * `attack` - Duration of the attack in seconds.
+    /// * `decay` - Duration of the decay in seconds.
+    /// * `sustain` - Level held until the release.
+    /// * `release` - Duration of the release in seconds.
+    /// * `duration` - Duration of the whole note in seconds.
+    ///
+    /// # Returns
+    /// * `Result<Envelope<T>>` - The generated envelope, or an error if a
+    ///   duration is negative or not finite.
+    pub fn adsr(attack: f64, decay: f64, sustain: T, release: f64, duration: f64) -> Result<Self> {
+        for d in [attack, decay, release, duration] {
+            check_duration(d)?;
+        }
+        let note_off = (duration - release).max(attack + decay);
+        Self::new(vec![
+            (0.0, T::zero()),
+            (attack, T::one()),
+            (attack + decay, sustain),
+            (note_off, sustain),
+            (note_off + release, T::zero()),
+        ])
+    }
+
+    /// Shapes a signal with the envelope.
+    ///
+    /// # Arguments
+    /// * `signal` - The signal to shape.
+    ///
+    /// # Returns
+    /// * `Product<S, Envelope<T>>` - The signal times the envelope.
+    pub fn apply<S: Signal<T>>(self, signal: S) -> Product<S, Envelope<T>> {
+        Product(signal, self)
+    }
+
+    /// Returns the level of the envelope at the given time.
+    ///
+    /// # Arguments
+    /// * `t` - The time in seconds.
+    ///
+    /// # Returns
+    /// * `T` - The interpolated level, 1 for an envelope without breakpoints.
+    pub fn level(&self, t: f64) -> T {
+        let i = self.points.partition_point(|&(time, _)| time <= t);
+        match (self.points.get(i.wrapping_sub(1)), self.points.get(i)) {
+            (None, None) => T::one(),
+            (Some(&(_, level)), None) | (None, Some(&(_, level))) => level,
+            (Some(&(t0, a)), Some(&(t1, b))) => a + (b - a) * T::from_f64((t - t0) / (t1 - t0)),
+        }
+    }
+}
+
+impl<T: Sample> Signal<T> for Envelope<T> {
+    /// Time of the last breakpoint, so plots show the whole envelope.
+    ///
+    /// # Returns
+    /// * `f64` - The duration of the envelope in seconds.
+    fn period(&self) -> f64 {
+        self.points.last().map_or(0.1, |&(t, _)| t)
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        Periodicity::Aperiodic
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        ts.iter().map(|&t| self.level(t)).collect()
+    }
+}
+
+impl_signal_ops!([T: Sample,] Envelope<T>);
+
+/// Represents the sum of two signals, which may be of different types.
+pub struct SumSignal<A, B = A>(A, B);
+
+impl<T: Sample, A: Signal<T>, B: Signal<T>> Signal<T> for SumSignal<A, B> {
+    /// Period of the signal.
+    ///
+    /// This is the fundamental period, see [`SumSignal::periodicity`].
+    /// When there is none, the longest period of the components is returned,
+    /// which is still reasonable for plotting.
+    ///
+    /// # Returns
+    /// * `f64` - The period of the signal in seconds.
+    fn period(&self) -> f64 {
+        period_or_fallback(self.periodicity(), || [self.0.period(), self.1.period()])
+    }
+
+    /// Fundamental period of the sum, see [`Periodicity::combine`].
+    ///
+    /// # Returns
+    /// * `Periodicity` - The fundamental period, or `Aperiodic`.
+    fn periodicity(&self) -> Periodicity {
+        self.0.periodicity().combine(self.1.periodicity())
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let samples_a = self.0.evaluate(ts);
+        let samples_b = self.1.evaluate(ts);
+        samples_a
+            .into_iter()
+            .zip(samples_b)
+            .map(|(a, b)| a + b)
+            .collect()
+    }
+}
+
+impl_signal_ops!([A, B,] SumSignal<A, B>);
+
+/// Period used to plot a combination of signals: the fundamental period
+/// when there is a finite one, else the longest finite period of the
+/// components, or the default 0.1 seconds.
+///
+/// # Arguments
+/// * `periodicity` - The periodicity of the combination.
+/// * `components` - The periods of the components, only computed when needed.
+///
+/// # Returns
+/// * `f64` - The period in seconds.
+fn period_or_fallback<I: IntoIterator<Item = f64>>(
+    periodicity: Periodicity,
+    components: impl FnOnce() -> I,
+) -> f64 {
+    match periodicity {
+        Periodicity::Periodic(period) if period.is_finite() => period,
+        _ => components()
+            .into_iter()
+            .filter(|p| p.is_finite())
+            .reduce(f64::max)
+            .unwrap_or(0.1),
+    }
+}
+
+/// Represents a weighted mix of any number of signals.
+pub struct Mix<T = f32> {
+    signals: Vec<(T, Box<dyn Signal<T> + Send + Sync>)>,
+}
+
+impl<T: Sample> Default for Mix<T> {
+    fn default() -> Self {
+        Self {
+            signals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Sample> Mix<T> {
+    /// Creates an empty mix, which evaluates to silence.
+    ///
+    /// # Returns
+    /// * `Mix<T>` - The empty mix.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a signal to the mix.
+    ///
+    /// # Arguments
+    /// * `weight` - The gain applied to the signal.
+    /// * `signal` - The signal to add.
+    ///
+    /// # Returns
+    /// * `Mix<T>` - The mix with the signal added.
+    pub fn with(mut self, weight: T, signal: impl Signal<T> + Send + Sync + 'static) -> Self {
+        self.push(weight, signal);
+        self
+    }
+
+    /// Adds a signal to the mix in place.
+    ///
+    /// # Arguments
+    /// * `weight` - The gain applied to the signal.
+    /// * `signal` - The signal to add.
+    pub fn push(&mut self, weight: T, signal: impl Signal<T> + Send + Sync + 'static) {
+        self.signals.push((weight, Box::new(signal)));
+    }
+
+    /// Returns the number of signals in the mix.
+    ///
+    /// # Returns
+    /// * `usize` - Number of signals.
+    pub fn len(&self) -> usize {
+        self.signals.len()
+    }
+
+    /// Returns `true` if the mix has no signals.
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty()
+    }
+}
+
+impl<T: Sample> Signal<T> for Mix<T> {
+    /// Period of the signal, like [`SumSignal::period`].
+    ///
+    /// # Returns
+    /// * `f64` - The period of the signal in seconds.
+    fn period(&self) -> f64 {
+        period_or_fallback(self.periodicity(), || {
+            self.signals.iter().map(|(_, s)| s.period())
+        })
+    }
+
+    /// Fundamental period of the mix, see [`Periodicity::combine`].
+    ///
+    /// # Returns
+    /// * `Periodicity` - The fundamental period, or `Aperiodic`.
+    fn periodicity(&self) -> Periodicity {
+        self.signals
+            .iter()
+            .filter(|(weight, _)| !weight.is_zero())
+            .map(|(_, s)| s.periodicity())
+            .fold(Periodicity::Periodic(f64::INFINITY), Periodicity::combine)
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let mut ys = vec![T::zero(); ts.len()];
+        for (weight, signal) in &self.signals {
+            for (y, s) in ys.iter_mut().zip(signal.evaluate(ts)) {
+                *y += *weight * s;
+            }
+        }
+        ys
+    }
+}
+
+impl_signal_ops!([T: Sample,] Mix<T>);
+
+impl<T: Sample, S: Signal<T> + Send + Sync + 'static> FromIterator<(T, S)> for Mix<T> {
+    fn from_iter<I: IntoIterator<Item = (T, S)>>(iter: I) -> Self {
+        let mut mix = Mix::new();
+        mix.extend(iter);
+        mix
+    }
+}
+
+impl<T: Sample, S: Signal<T> + Send + Sync + 'static> Extend<(T, S)> for Mix<T> {
+    fn extend<I: IntoIterator<Item = (T, S)>>(&mut self, iter: I) {
+        for (weight, signal) in iter {
+            self.push(weight, signal);
+        }
+    }
+}
+
+/// Represents the product of two signals, e.g. ring or amplitude modulation.
+pub struct Product<A, B = A>(A, B);
+
+impl<T: Sample, A: Signal<T>, B: Signal<T>> Signal<T> for Product<A, B> {
+    /// Period of the signal, like [`SumSignal::period`].
+    ///
+    /// # Returns
+    /// * `f64` - The period of the signal in seconds.
+    fn period(&self) -> f64 {
+        period_or_fallback(self.periodicity(), || [self.0.period(), self.1.period()])
+    }
+
+    /// Period shared by both factors, see [`Periodicity::combine`].
+    ///
+    /// NOTE: the product may repeat sooner, e.g. cos² repeats twice per period of cos.
+    ///
+    /// # Returns
+    /// * `Periodicity` - The common period, or `Aperiodic`.
+    fn periodicity(&self) -> Periodicity {
+        self.0.periodicity().combine(self.1.periodicity())
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let samples_a = self.0.evaluate(ts);
+        let samples_b = self.1.evaluate(ts);
+        samples_a
+            .into_iter()
+            .zip(samples_b)
+            .map(|(a, b)| a * b)
+            .collect()
+    }
+}
+
+impl_signal_ops!([A, B,] Product<A, B>);
+
+/// Represents a signal multiplied by a constant.
+pub struct Scaled<S, T = f32>(S, T);
+
+impl<T: Sample, S: Signal<T>> Signal<T> for Scaled<S, T> {
+    fn period(&self) -> f64 {
+        self.0.period()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        self.0.periodicity()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let mut ys = self.0.evaluate(ts);
+        ys.iter_mut().for_each(|y| *y *= self.1);
+        ys
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        self.0.evaluate_into(ts, out);
+        out.iter_mut().for_each(|y| *y *= self.1);
+    }
+}
+
+impl_signal_ops!([S, T,] Scaled<S, T>);
+
+/// Represents a signal shifted in time by a number of seconds.
+pub struct Delayed<S>(S, f64);
+
+impl<T: Sample, S: Signal<T>> Signal<T> for Delayed<S> {
+    fn period(&self) -> f64 {
+        self.0.period()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        self.0.periodicity()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let shifted: Vec<f64> = ts.iter().map(|&t| t - self.1).collect();
+        self.0.evaluate(&shifted)
+    }
+}
+
+impl_signal_ops!([S,] Delayed<S>);
+
+/// Represents a signal played faster or slower by a constant factor.
+pub struct TimeScaled<S>(S, f64);
+
+impl<T: Sample, S: Signal<T>> Signal<T> for TimeScaled<S> {
+    fn period(&self) -> f64 {
+        self.0.period() / self.1.abs()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        match self.0.periodicity() {
+            Periodicity::Periodic(period) => Periodicity::Periodic(period / self.1.abs()),
+            Periodicity::Aperiodic => Periodicity::Aperiodic,
+        }
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let scaled: Vec<f64> = ts.iter().map(|&t| t * self.1).collect();
+        self.0.evaluate(&scaled)
+    }
+}
+
+impl_signal_ops!([S,] TimeScaled<S>);
+
+/// Represents a signal with its sign flipped.
+pub struct Negated<S>(S);
+
+impl<T: Sample, S: Signal<T>> Signal<T> for Negated<S> {
+    fn period(&self) -> f64 {
+        self.0.period()
+    }
+
+    fn periodicity(&self) -> Periodicity {
+        self.0.periodicity()
+    }
+
+    fn evaluate(&self, ts: &[f64]) -> Vec<T> {
+        let mut ys = self.0.evaluate(ts);
+        ys.iter_mut().for_each(|y| *y = -*y);
+        ys
+    }
+
+    fn evaluate_into(&self, ts: &[f64], out: &mut [T]) {
+        self.0.evaluate_into(ts, out);
+        out.iter_mut().for_each(|y| *y = -*y);
+    }
+}
+
+impl_signal_ops!([S,] Negated<S>);
@@ -0,0 +1,360 @@
+//! The discrete Fourier and cosine transforms written as linear algebra,
+//! following chapters 6 and 7 of the book.
+//!
+//! A wave is synthesized by multiplying a matrix of sinusoids, one column per
+//! frequency and one row per time, by a vector of amplitudes. Analysis goes
+//! the other way: solving that linear system, or, when the columns are
+//! orthogonal, multiplying by the (conjugate) transpose.
+//! https://github.com/AllenDowney/ThinkDSP/blob/f1cc15de31f658d5df287332a30659fb16eb41d5/code/chap07.ipynb
+
+use std::{cmp::Ordering, f64::consts::TAU, ops::Index};
+
+use num_complex::ComplexFloat;
+use num_traits::Float;
+
+use super::{Complex, ComplexWave, Sample, Wave, cycles};
+use crate::error::{Error, Result, check_len};
+
+/// A dense matrix, stored row by row.
+///
+/// `E` is the type of the elements: a [`Sample`] or a [`Complex`] of one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<E> {
+    rows: usize,
+    cols: usize,
+    data: Vec<E>,
+}
+
+impl<E: ComplexFloat> Matrix<E> {
+    /// Creates a matrix from a function of the row and column indices.
+    ///
+    /// # Arguments
+    /// * `rows` - The number of rows.
+    /// * `cols` - The number of columns.
+    /// * `f` - The element at row `i` and column `j` is `f(i, j)`.
+    ///
+    /// # Returns
+    /// * `Matrix<E>` - The generated matrix.
+    pub fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> E) -> Self {
+        let data = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(|(i, j)| f(i, j))
+            .collect();
+        Self { rows, cols, data }
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the conjugate transpose, which is the plain transpose for real matrices.
+    ///
+    /// # Returns
+    /// * `Matrix<E>` - The conjugate transpose.
+    pub fn conj_transpose(&self) -> Self {
+        Self::from_fn(self.cols, self.rows, |i, j| self[(j, i)].conj())
+    }
+
+    /// Multiplies the matrix by a column vector.
+    ///
+    /// # Arguments
+    /// * `v` - The vector, with one element per column.
+    ///
+    /// # Returns
+    /// * `Result<Vec<E>>` - The product, with one element per row, or an
+    ///   error if the vector doesn't have one element per column.
+    pub fn mul_vec(&self, v: &[E]) -> Result<Vec<E>> {
+        check_len(self.cols, v.len())?;
+        Ok(self.product(v))
+    }
+
+    /// Product by a vector already known to have one element per column.
+    fn product(&self, v: &[E]) -> Vec<E> {
+        (0..self.rows)
+            .map(|i| {
+                let row = &self.data[i * self.cols..(i + 1) * self.cols];
+                row.iter()
+                    .zip(v)
+                    .fold(E::zero(), |acc, (&m, &x)| acc + m * x)
+            })
+            .collect()
+    }
+
+    /// Solves the linear system `self * x = b`, by Gaussian elimination with partial pivoting.
+    ///
+    /// # Arguments
+    /// * `b` - The right-hand side, with one element per row.
+    ///
+    /// # Returns
+    /// * `Result<Vec<E>>` - The solution, or an error if the matrix isn't
+    ///   square, `b` doesn't have one element per row, or the matrix is singular.
+    pub fn solve(&self, b: &[E]) -> Result<Vec<E>> {
+        let n = self.rows;
+        check_len(n, self.cols)?;
+        check_len(n, b.len())?;
+        let mut a = self.data.clone();
+        let mut x = b.to_vec();
+
+        for col in 0..n {
+            let pivot = (col..n).max_by(|&i, &j| {
+                a[i * n + col]
+                    .abs()
+                    .partial_cmp(&a[j * n + col].abs())
+                    .unwrap_or(Ordering::Equal)
+            });
+            let Some(pivot) = pivot else {
+                return Err(Error::Singular);
+            };
+            if a[pivot * n + col].abs() <= <E::Real as Float>::epsilon() {
+                return Err(Error::Singular);
+            }
+            if pivot != col {
+                for k in 0..n {
+                    a.swap(pivot * n + k, col * n + k);
+                }
+                x.swap(pivot, col);
+            }
+            for row in col + 1..n {
+                let factor = a[row * n + col] / a[col * n + col];
+                for k in col..n {
+                    a[row * n + k] = a[row * n + k] - factor * a[col * n + k];
+                }
+                x[row] = x[row] - factor * x[col];
+            }
+        }
+
+        for row in (0..n).rev() {
+            let known = (row + 1..n).fold(E::zero(), |acc, k| acc + a[row * n + k] * x[k]);
+            x[row] = (x[row] - known) / a[row * n + row];
+        }
+        Ok(x)
+    }
+}
+
+impl<E> Index<(usize, usize)> for Matrix<E> {
+    type Output = E;
+
+    fn index(&self, (i, j): (usize, usize)) -> &E {
+        assert!(i < self.rows && j < self.cols, "index out of bounds");
+        &self.data[i * self.cols + j]
+    }
+}
+
+/// Builds the matrix of complex sinusoids `e^{i 2π f t}`, one row per time and one column per frequency.
+///
+/// # Arguments
+/// * `ts` - The times in seconds.
+/// * `fs` - The frequencies in Hz.
+///
+/// # Returns
+/// * `Matrix<Complex<T>>` - The synthesis matrix.
+pub fn synthesis_matrix<T: Sample>(ts: &[f64], fs: &[f64]) -> Matrix<Complex<T>> {
+    Matrix::from_fn(ts.len(), fs.len(), |i, j| {
+        Complex::from_polar(T::one(), T::from_f64(TAU * cycles(fs[j], ts[i])))
+    })
+}
+
+/// Builds the matrix of cosines `cos(2π f t)`, one row per time and one column per frequency.
+///
+/// # Arguments
+/// * `ts` - The times in seconds.
+/// * `fs` - The frequencies in Hz.
+///
+/// # Returns
+/// * `Matrix<T>` - The synthesis matrix.
+pub fn dct_matrix<T: Sample>(ts: &[f64], fs: &[f64]) -> Matrix<T> {
+    Matrix::from_fn(ts.len(), fs.len(), |i, j| {
+        T::from_f64((TAU * cycles(fs[j], ts[i])).cos())
+    })
+}
+
+/// Adds up complex sinusoids, one per frequency, sampled at the given times.
+///
+/// # Arguments
+/// * `amps` - The complex amplitudes: the magnitude is the amplitude and
+///   the argument the phase offset of each sinusoid.
+/// * `fs` - The frequencies in Hz.
+/// * `ts` - The times in seconds, evenly spaced.
+///
+/// # Returns
+/// * `Result<ComplexWave<T>>` - The synthesized wave, or an error if there
+///   isn't one amplitude per frequency.
+pub fn synthesize<T: Sample>(
+    amps: &[Complex<T>],
+    fs: &[f64],
+    ts: &[f64],
+) -> Result<ComplexWave<T>> {
+    let ys = synthesis_matrix(ts, fs).mul_vec(amps)?;
+    ComplexWave::new(ys, ts.to_vec(), framerate_of(ts))
+}
+
+/// Finds the amplitudes of the given frequencies in a wave, by solving the linear system.
+///
+/// # Arguments
+/// * `wave` - The wave, with as many samples as frequencies.
+/// * `fs` - The frequencies in Hz.
+///
+/// # Returns
+/// * `Result<Vec<Complex<T>>>` - The complex amplitudes, or an error if
+///   there isn't one frequency per sample or they can't be told apart at
+///   these times.
+pub fn analyze<T: Sample>(wave: &ComplexWave<T>, fs: &[f64]) -> Result<Vec<Complex<T>>> {
+    synthesis_matrix(&wave.ts(), fs).solve(wave.ys())
+}
+
+/// Finds the amplitudes of the given frequencies in a wave, assuming the
+/// synthesis matrix is orthogonal up to a factor `N`, i.e. `M* M = N I`.
+///
+/// This holds for `N` samples spanning one second and the integer frequencies
+/// `0..N`, the setting of the DFT, and avoids solving the system.
+///
+/// # Arguments
+/// * `wave` - The wave.
+/// * `fs` - The frequencies in Hz.
+///
+/// # Returns
+/// * `Vec<Complex<T>>` - The complex amplitudes.
+pub fn analyze_orthogonal<T: Sample>(wave: &ComplexWave<T>, fs: &[f64]) -> Vec<Complex<T>> {
+    let n = T::from_f64(wave.len() as f64);
+    synthesis_matrix(&wave.ts(), fs)
+        .conj_transpose()
+        .product(wave.ys())
+        .into_iter()
+        .map(|amp| amp / n)
+        .collect()
+}
+
+/// Discrete Fourier transform: the amplitudes of the `N` frequencies `0..N`
+/// in `N` samples, times `N`.
+///
+/// # Arguments
+/// * `ys` - The samples.
+///
+/// # Returns
+/// * `Vec<Complex<T>>` - The transform.
+pub fn dft<T: Sample>(ys: &[Complex<T>]) -> Vec<Complex<T>> {
+    let (ts, fs) = dft_grid(ys.len());
+    synthesis_matrix(&ts, &fs).conj_transpose().product(ys)
+}
+
+/// Inverse discrete Fourier transform, see [`dft`].
+///
+/// # Arguments
+/// * `amps` - The transform.
+///
+/// # Returns
+/// * `Vec<Complex<T>>` - The samples.
+pub fn idft<T: Sample>(amps: &[Complex<T>]) -> Vec<Complex<T>> {
+    let (ts, fs) = dft_grid(amps.len());
+    let n = T::from_f64(amps.len() as f64);
+    synthesis_matrix(&ts, &fs)
+        .product(amps)
+        .into_iter()
+        .map(|y| y / n)
+        .collect()
+}
+
+/// Adds up cosines, one per frequency, sampled at the given times.
+///
+/// # Arguments
+/// * `amps` - The amplitudes.
+/// * `fs` - The frequencies in Hz.
+/// * `ts` - The times in seconds, evenly spaced.
+///
+/// # Returns
+/// * `Result<Wave<T>>` - The synthesized wave, or an error if there isn't
+///   one amplitude per frequency.
+pub fn synthesize_dct<T: Sample>(amps: &[T], fs: &[f64], ts: &[f64]) -> Result<Wave<T>> {
+    let ys = dct_matrix(ts, fs).mul_vec(amps)?;
+    Wave::new(ys, ts.to_vec(), framerate_of(ts))
+}
+
+/// Finds the amplitudes of the given cosines in a wave, by solving the linear system.
+///
+/// # Arguments
+/// * `wave` - The wave, with as many samples as frequencies.
+/// * `fs` - The frequencies in Hz.
+///
+/// # Returns
+/// * `Result<Vec<T>>` - The amplitudes, or an error if there isn't one
+///   frequency per sample or the cosines can't be told apart at these times.
+pub fn analyze_dct<T: Sample>(wave: &Wave<T>, fs: &[f64]) -> Result<Vec<T>> {
+    dct_matrix(&wave.ts(), fs).solve(wave.ys())
+}
+
+/// Finds the amplitudes of the given cosines in a wave, assuming the
+/// synthesis matrix is orthogonal up to a factor `N / 2`, i.e. `Mᵀ M = N/2 I`.
+///
+/// This holds for the times and frequencies of the DCT-IV, see [`dct_iv`].
+///
+/// # Arguments
+/// * `wave` - The wave.
+/// * `fs` - The frequencies in Hz.
+///
+/// # Returns
+/// * `Vec<T>` - The amplitudes.
+pub fn analyze_dct_orthogonal<T: Sample>(wave: &Wave<T>, fs: &[f64]) -> Vec<T> {
+    let scale = T::from_f64(2.0 / wave.len() as f64);
+    dct_matrix(&wave.ts(), fs)
+        .conj_transpose()
+        .product(wave.ys())
+        .into_iter()
+        .map(|amp| amp * scale)
+        .collect()
+}
+
+/// Discrete cosine transform, type IV: `M ys` with the times `(k + 0.5) / N`
+/// and the frequencies `(k + 0.5) / 2`.
+///
+/// NOTE: the book divides by 2, which only makes it its own inverse for `N = 4`;
+/// here the scaling is left to [`inverse_dct_iv`], like the DFT's.
+///
+/// # Arguments
+/// * `ys` - The samples.
+///
+/// # Returns
+/// * `Vec<T>` - The transform.
+pub fn dct_iv<T: Sample>(ys: &[T]) -> Vec<T> {
+    let (ts, fs) = dct_iv_grid(ys.len());
+    dct_matrix(&ts, &fs).product(ys)
+}
+
+/// Inverse of [`dct_iv`], which is the same transform scaled by `2 / N`.
+///
+/// # Arguments
+/// * `amps` - The transform.
+///
+/// # Returns
+/// * `Vec<T>` - The samples.
+pub fn inverse_dct_iv<T: Sample>(amps: &[T]) -> Vec<T> {
+    let scale = T::from_f64(2.0 / amps.len() as f64);
+    dct_iv(amps).into_iter().map(|y| y * scale).collect()
+}
+
+/// Times `k / N` and frequencies `k` of the DFT of `n` samples.
+pub fn dft_grid(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let ts = (0..n).map(|k| k as f64 / n as f64).collect();
+    let fs = (0..n).map(|k| k as f64).collect();
+    (ts, fs)
+}
+
+/// Times `(k + 0.5) / N` and frequencies `(k + 0.5) / 2` of the DCT-IV of `n` samples.
+pub fn dct_iv_grid(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let ts = (0..n).map(|k| (k as f64 + 0.5) / n as f64).collect();
+    let fs = (0..n).map(|k| (k as f64 + 0.5) / 2.0).collect();
+    (ts, fs)
+}
+
+/// Framerate of evenly spaced times, 1 when there aren't two of them.
+fn framerate_of(ts: &[f64]) -> u64 {
+    match ts {
+        [a, b, ..] => (1.0 / (b - a)).round() as u64,
+        _ => 1,
+    }
+}
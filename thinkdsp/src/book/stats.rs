@@ -0,0 +1,317 @@
+//! Statistics and level metering of waves: RMS, peak, crest factor, DC
+//! offset, zero-crossing rate, energy, dBFS and loudness.
+//!
+//! Loudness follows ITU-R BS.1770-4: the samples are K-weighted, mean squares
+//! are taken over 400 ms blocks overlapping by 75 %, and the blocks below an
+//! absolute gate of -70 LUFS and a relative gate 10 LU under the mean are dropped.
+//! https://www.itu.int/rec/R-REC-BS.1770
+
+use super::{Sample, Wave};
+use crate::error::{Error, Result, check_duration};
+
+/// Level under which a block counts as silence and isn't measured, in LUFS.
+const ABSOLUTE_GATE: f64 = -70.0;
+
+/// Distance of the relative gate below the ungated loudness, in LU.
+const RELATIVE_GATE: f64 = 10.0;
+
+/// Duration of a gating block, in seconds.
+const BLOCK: f64 = 0.4;
+
+/// Step between gating blocks, in seconds: 75 % overlap.
+const BLOCK_STEP: f64 = 0.1;
+
+/// A statistic of the samples of a wave, see [`Wave::windowed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+    /// See [`Wave::rms`].
+    Rms,
+    /// See [`Wave::peak`].
+    Peak,
+    /// See [`Wave::crest_factor`].
+    CrestFactor,
+    /// See [`Wave::dc_offset`].
+    DcOffset,
+    /// See [`Wave::zero_crossing_rate`].
+    ZeroCrossingRate,
+    /// See [`Wave::energy`].
+    Energy,
+    /// See [`Wave::dbfs`].
+    Dbfs,
+    /// Ungated loudness of each window, e.g. the momentary loudness for
+    /// 400 ms windows or the short-term loudness for 3 s ones, see [`Wave::loudness`].
+    Loudness,
+}
+
+impl Statistic {
+    /// Value of the statistic over `ys`, sampled at `framerate`.
+    ///
+    /// For [`Statistic::Loudness`], `ys` must already be K-weighted.
+    fn of<T: Sample>(self, ys: &[T], framerate: u64) -> f64 {
+        match self {
+            Statistic::Rms => mean_square(ys).sqrt(),
+            Statistic::Peak => ys.iter().fold(0.0, |peak, y| peak.max(y.into_f64().abs())),
+            Statistic::CrestFactor => {
+                let rms = Statistic::Rms.of(ys, framerate);
+                if rms == 0.0 {
+                    0.0
+                } else {
+                    Statistic::Peak.of(ys, framerate) / rms
+                }
+            }
+            Statistic::DcOffset if ys.is_empty() => 0.0,
+            Statistic::DcOffset => ys.iter().map(|y| y.into_f64()).sum::<f64>() / ys.len() as f64,
+            Statistic::ZeroCrossingRate if ys.is_empty() => 0.0,
+            Statistic::ZeroCrossingRate => {
+                let crossings = ys
+                    .windows(2)
+                    .filter(|w| (w[0].into_f64() < 0.0) != (w[1].into_f64() < 0.0))
+                    .count();
+                crossings as f64 * framerate as f64 / ys.len() as f64
+            }
+            Statistic::Energy => ys.iter().map(|y| y.into_f64().powi(2)).sum(),
+            Statistic::Dbfs => 20.0 * Statistic::Rms.of(ys, framerate).log10(),
+            Statistic::Loudness => block_loudness(mean_square(ys)),
+        }
+    }
+}
+
+impl<T: Sample> Wave<T> {
+    /// Returns the root mean square of the samples.
+    ///
+    /// # Returns
+    /// * `f64` - The RMS, `1/√2` for a full-scale sine and 0 for an empty wave.
+    pub fn rms(&self) -> f64 {
+        self.statistic(Statistic::Rms)
+    }
+
+    /// Returns the largest absolute value of the samples.
+    ///
+    /// # Returns
+    /// * `f64` - The peak, 0 for an empty wave.
+    pub fn peak(&self) -> f64 {
+        self.statistic(Statistic::Peak)
+    }
+
+    /// Returns the peak over the RMS: `√2` for a sine, 1 for a square wave.
+    ///
+    /// # Returns
+    /// * `f64` - The crest factor, 0 for silence.
+    pub fn crest_factor(&self) -> f64 {
+        self.statistic(Statistic::CrestFactor)
+    }
+
+    /// Returns the mean of the samples.
+    ///
+    /// # Returns
+    /// * `f64` - The DC offset, 0 for an empty wave.
+    pub fn dc_offset(&self) -> f64 {
+        self.statistic(Statistic::DcOffset)
+    }
+
+    /// Returns how often the samples change sign.
+    ///
+    /// # Returns
+    /// * `f64` - The number of zero crossings per second, twice the
+    ///   frequency for a sinusoid.
+    pub fn zero_crossing_rate(&self) -> f64 {
+        self.statistic(Statistic::ZeroCrossingRate)
+    }
+
+    /// Returns the sum of the squares of the samples.
+    ///
+    /// # Returns
+    /// * `f64` - The energy.
+    pub fn energy(&self) -> f64 {
+        self.statistic(Statistic::Energy)
+    }
+
+    /// Returns the RMS level relative to full scale.
+    ///
+    /// A full-scale square wave reads 0 dBFS and a full-scale sine -3.01 dBFS.
+    ///
+    /// # Returns
+    /// * `f64` - The level in dBFS, minus infinity for silence.
+    pub fn dbfs(&self) -> f64 {
+        self.statistic(Statistic::Dbfs)
+    }
+
+    /// Returns the integrated loudness of the wave, K-weighted and gated as in BS.1770.
+    ///
+    /// A full-scale 1 kHz sine reads -3.01 LUFS.
+    ///
+    /// # Returns
+    /// * `Result<f64>` - The loudness in LUFS, minus infinity if the wave is
+    ///   shorter than a block or every block is gated out, or an error if the
+    ///   framerate is too low for the K-weighting filter.
+    pub fn loudness(&self) -> Result<f64> {
+        let weighted = k_weighted(&self.ys, self.framerate)?;
+        let block = (BLOCK * self.framerate as f64).round() as usize;
+        let step = (BLOCK_STEP * self.framerate as f64).round() as usize;
+        let powers: Vec<f64> = windows(weighted.len(), block, step)
+            .map(|start| mean_square(&weighted[start..start + block]))
+            .filter(|&z| block_loudness(z) > ABSOLUTE_GATE)
+            .collect();
+        let gate = block_loudness(mean(&powers)) - RELATIVE_GATE;
+        let gated: Vec<f64> = powers
+            .into_iter()
+            .filter(|&z| block_loudness(z) > gate)
+            .collect();
+        Ok(block_loudness(mean(&gated)))
+    }
+
+    /// Returns a statistic of the samples in windows sliding over the wave.
+    ///
+    /// Only whole windows are measured; window `i` starts at `i * hop`
+    /// seconds after the first sample.
+    ///
+    /// # Arguments
+    /// * `statistic` - What to measure.
+    /// * `window` - The duration of a window in seconds.
+    /// * `hop` - The time between the starts of two windows in seconds.
+    ///
+    /// # Returns
+    /// * `Result<Vec<f64>>` - The value of each window, or an error if the
+    ///   window or the hop is shorter than a sample, or the framerate is too
+    ///   low for the K-weighting filter of [`Statistic::Loudness`].
+    pub fn windowed(&self, statistic: Statistic, window: f64, hop: f64) -> Result<Vec<f64>> {
+        let samples = |name, duration: f64| {
+            check_duration(duration)?;
+            match (duration * self.framerate as f64).round() as usize {
+                0 => Err(Error::OutOfRange {
+                    name,
+                    value: duration,
+                }),
+                n => Ok(n),
+            }
+        };
+        let (size, step) = (samples("window", window)?, samples("hop", hop)?);
+        Ok(match statistic {
+            Statistic::Loudness => {
+                let weighted = k_weighted(&self.ys, self.framerate)?;
+                measure(statistic, &weighted, size, step, self.framerate)
+            }
+            _ => measure(statistic, &self.ys, size, step, self.framerate),
+        })
+    }
+
+    /// Value of a statistic over the whole wave.
+    fn statistic(&self, statistic: Statistic) -> f64 {
+        statistic.of(&self.ys, self.framerate)
+    }
+}
+
+/// Starts of the whole windows of `size` samples, `step` apart, in `len` samples.
+fn windows(len: usize, size: usize, step: usize) -> impl Iterator<Item = usize> {
+    (0..)
+        .map(move |i| i * step)
+        .take_while(move |&start| start + size <= len)
+}
+
+/// Value of a statistic over each whole window of `size` samples, `step` apart.
+fn measure<T: Sample>(
+    statistic: Statistic,
+    ys: &[T],
+    size: usize,
+    step: usize,
+    framerate: u64,
+) -> Vec<f64> {
+    windows(ys.len(), size, step)
+        .map(|start| statistic.of(&ys[start..start + size], framerate))
+        .collect()
+}
+
+/// Mean of the squares of the samples, 0 if there are none.
+fn mean_square<T: Sample>(ys: &[T]) -> f64 {
+    if ys.is_empty() {
+        return 0.0;
+    }
+    ys.iter().map(|y| y.into_f64().powi(2)).sum::<f64>() / ys.len() as f64
+}
+
+/// Mean of the values, 0 if there are none.
+fn mean(xs: &[f64]) -> f64 {
+    if xs.is_empty() {
+        return 0.0;
+    }
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+/// Loudness of a block of K-weighted samples with the given mean square, in LUFS.
+fn block_loudness(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+/// Applies the K-weighting of BS.1770 to the samples: a high shelf of about
+/// +4 dB above 1.5 kHz, modelling the head, then a high-pass at 38 Hz.
+fn k_weighted<T: Sample>(ys: &[T], framerate: u64) -> Result<Vec<f64>> {
+    let mut xs: Vec<f64> = ys.iter().map(|y| y.into_f64()).collect();
+    Biquad::shelf(framerate)?.filter(&mut xs);
+    Biquad::high_pass(framerate)?.filter(&mut xs);
+    Ok(xs)
+}
+
+/// A second-order IIR filter, normalized so `a[0]` is 1.
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+}
+
+impl Biquad {
+    /// First stage of the K-weighting.
+    ///
+    /// The standard only gives the coefficients at 48 kHz; these are the
+    /// analog prototype they come from, as in libebur128, so any framerate
+    /// above twice the shelf frequency works.
+    fn shelf(framerate: u64) -> Result<Self> {
+        const F0: f64 = 1681.974450955533;
+        const GAIN_DB: f64 = 3.999843853973347;
+        const Q: f64 = 0.7071752369554196;
+        let k = Self::prewarp(F0, framerate)?;
+        let vh = 10f64.powf(GAIN_DB / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / Q + k * k;
+        Ok(Self {
+            b: [
+                (vh + vb * k / Q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / Q + k * k) / a0,
+            ],
+            a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / Q + k * k) / a0],
+        })
+    }
+
+    /// Second stage of the K-weighting, the RLB high-pass.
+    fn high_pass(framerate: u64) -> Result<Self> {
+        const F0: f64 = 38.13547087602444;
+        const Q: f64 = 0.5003270373238773;
+        let k = Self::prewarp(F0, framerate)?;
+        let a0 = 1.0 + k / Q + k * k;
+        Ok(Self {
+            b: [1.0, -2.0, 1.0],
+            a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / Q + k * k) / a0],
+        })
+    }
+
+    /// `tan(π f0 / framerate)`, for the bilinear transform.
+    fn prewarp(f0: f64, framerate: u64) -> Result<f64> {
+        if (framerate as f64) <= 2.0 * f0 {
+            return Err(Error::OutOfRange {
+                name: "framerate",
+                value: framerate as f64,
+            });
+        }
+        Ok((std::f64::consts::PI * f0 / framerate as f64).tan())
+    }
+
+    /// Filters the samples in place, in transposed direct form II.
+    fn filter(&self, xs: &mut [f64]) {
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for x in xs {
+            let y = self.b[0] * *x + s1;
+            s1 = self.b[1] * *x - self.a[1] * y + s2;
+            s2 = self.b[2] * *x - self.a[2] * y;
+            *x = y;
+        }
+    }
+}
@@ -21,16 +21,16 @@ pub trait Signal {
         unimplemented!()
     }
 
-    /// Evaluates the signal at the given times.
-    ///
-    /// # Arguments
-    /// * `ts` - A vector of time points (in seconds) at which to evaluate the signal.
-    ///
-    /// # Returns
-    /// * `Vec<f32>` - The values of the signal at the given time points.
-    ///
-    /// NOTE: In the book's implementation subclasses as SumSinusoid implements `evaluate` and because Python is dynamic
-    /// call `evaluate` from `make_wave` works, in Rust we need to be explicit and add it as part of the trait.
+    // Evaluates the signal at the given times.
+    //
+    // # Arguments
+    // * `ts` - A vector of time points (in seconds) at which to evaluate the signal.
+    //
+    // # Returns
+    // * `Vec<f32>` - The values of the signal at the given time points.
+    //
+    // NOTE: In the book's implementation subclasses as SumSinusoid implements `evaluate` and because Python is dynamic
+    // call `evaluate` from `make_wave` works, in Rust we need to be explicit and add it as part of the trait.
     // fn evaluate(&self, ts: &Vec<f32>) -> Vec<f32>;
 
     /// Creates a wave from the signal.
@@ -80,6 +80,11 @@ impl Wave {
     pub fn len(&self) -> usize {
         self.ys.len()
     }
+
+    /// Returns `true` if the wave has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.ys.is_empty()
+    }
 }
 
 /// Represents a sinusoidal signal.
@@ -0,0 +1,122 @@
+//! Errors reported by the library.
+//!
+//! Constructors and operations validate their arguments and return a [`Result`],
+//! so invalid input never ends in a panic, an exit or a silent NaN.
+
+use std::fmt;
+
+/// Everything that can go wrong in the library.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Two sequences that must be as long as each other aren't,
+    /// e.g. the samples and the times of a wave.
+    LengthMismatch { expected: usize, found: usize },
+    /// A framerate of zero samples per second.
+    ZeroFramerate,
+    /// A frequency that is zero, negative or not finite where a positive one is needed.
+    InvalidFrequency(f64),
+    /// A duration that is negative or not finite.
+    InvalidDuration(f64),
+    /// A time that is not finite.
+    InvalidTime(f64),
+    /// A parameter outside of the values it accepts.
+    OutOfRange { name: &'static str, value: f64 },
+    /// A linear system without a unique solution.
+    Singular,
+    /// A plot could not be drawn.
+    Render(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::LengthMismatch { expected, found } => {
+                write!(f, "expected {expected} elements, found {found}")
+            }
+            Error::ZeroFramerate => write!(f, "framerate must be positive"),
+            Error::InvalidFrequency(freq) => write!(f, "invalid frequency: {freq} Hz"),
+            Error::InvalidDuration(duration) => write!(f, "invalid duration: {duration} s"),
+            Error::InvalidTime(t) => write!(f, "invalid time: {t} s"),
+            Error::OutOfRange { name, value } => write!(f, "{name} out of range: {value}"),
+            Error::Singular => write!(f, "singular matrix"),
+            Error::Render(e) => write!(f, "error rendering: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Result of the fallible operations of the library.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Checks that a framerate is positive.
+pub(crate) fn check_framerate(framerate: u64) -> Result<()> {
+    if framerate == 0 {
+        return Err(Error::ZeroFramerate);
+    }
+    Ok(())
+}
+
+/// Checks that a frequency is positive and finite.
+pub(crate) fn check_frequency(freq: f64) -> Result<()> {
+    if !(freq.is_finite() && freq > 0.0) {
+        return Err(Error::InvalidFrequency(freq));
+    }
+    Ok(())
+}
+
+/// Checks that a duration is positive or zero, and finite.
+pub(crate) fn check_duration(duration: f64) -> Result<()> {
+    if !(duration.is_finite() && duration >= 0.0) {
+        return Err(Error::InvalidDuration(duration));
+    }
+    Ok(())
+}
+
+/// Checks that a time is finite.
+pub(crate) fn check_time(t: f64) -> Result<()> {
+    if !t.is_finite() {
+        return Err(Error::InvalidTime(t));
+    }
+    Ok(())
+}
+
+/// Checks that times are finite and never decrease.
+pub(crate) fn check_times(ts: &[f64]) -> Result<()> {
+    for (i, &t) in ts.iter().enumerate() {
+        check_time(t)?;
+        if i > 0 && t < ts[i - 1] {
+            return Err(Error::OutOfRange {
+                name: "decreasing time",
+                value: t,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a sequence has the expected length.
+pub(crate) fn check_len(expected: usize, found: usize) -> Result<()> {
+    if expected != found {
+        return Err(Error::LengthMismatch { expected, found });
+    }
+    Ok(())
+}
+
+/// Checks that a parameter is within `range`.
+pub(crate) fn check_range<V>(
+    name: &'static str,
+    value: V,
+    range: impl std::ops::RangeBounds<V>,
+) -> Result<()>
+where
+    V: PartialOrd + Into<f64> + Copy,
+{
+    if !range.contains(&value) {
+        return Err(Error::OutOfRange {
+            name,
+            value: value.into(),
+        });
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
 pub mod book;
 pub mod dummy;
-mod render;
+pub mod error;
+pub mod render;
 mod tests;
@@ -1,30 +1,299 @@
 use eframe::egui;
-use egui_plot::{Legend, Line, Plot, PlotPoint, PlotPoints};
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+
+use crate::book::Sample;
+use crate::error::{Error, Result};
 
 // TODO: render in the browser with WASM
 // start from this removed code: https://github.com/magecnion/learning-dsp/commit/655f0b178502eb2562586b8713653eaa0082a192
 
-#[derive(Default)]
-struct MyPlot {
-    plot_points: Vec<PlotPoint>,
+/// Environment variable used to pick the backend, either `native` or `terminal`.
+pub const BACKEND_ENV: &str = "THINKDSP_BACKEND";
+
+/// Where plots are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Native egui window.
+    Native,
+    /// Unicode braille plot printed to stdout, `width` x `height` characters.
+    Terminal { width: usize, height: usize },
+}
+
+impl Backend {
+    /// Terminal backend sized to the current terminal (`COLUMNS`), or 80 columns.
+    pub fn terminal() -> Self {
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or(80);
+        Backend::Terminal { width, height: 20 }
+    }
+
+    /// Picks the backend from `THINKDSP_BACKEND`.
+    ///
+    /// When the variable is not set, falls back to the terminal if there is
+    /// no display to open a window on (e.g. over SSH).
+    pub fn from_env() -> Self {
+        match std::env::var(BACKEND_ENV).as_deref() {
+            Ok("terminal") => Backend::terminal(),
+            Ok("native") => Backend::Native,
+            _ if !has_display() => Backend::terminal(),
+            _ => Backend::Native,
+        }
+    }
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Backend::from_env()
+    }
+}
+
+fn has_display() -> bool {
+    if cfg!(all(unix, not(target_os = "macos"))) {
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    } else {
+        true
+    }
+}
+
+/// How the samples of a series are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// Samples joined by a line.
+    #[default]
+    Line,
+    /// A vertical stem from zero to each sample, topped by a dot.
+    Stems,
+    /// A dot on each sample.
+    Markers,
+}
+
+/// The x coordinates of a series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Times<'a> {
+    /// Sample `i` is at `start + i / framerate`, computed when drawn.
+    Uniform { start: f64, framerate: u64 },
+    /// One time per sample, sorted in ascending order.
+    Explicit(&'a [f64]),
 }
 
-impl eframe::App for MyPlot {
+impl Times<'_> {
+    /// Returns the time of sample `i`.
+    fn get(&self, i: usize) -> f64 {
+        match self {
+            Times::Uniform { start, framerate } => start + i as f64 / *framerate as f64,
+            Times::Explicit(ts) => ts[i],
+        }
+    }
+
+    /// Number of the first `n` times below `t`, or up to it when `inclusive`.
+    fn count(&self, n: usize, t: f64, inclusive: bool) -> usize {
+        let before = |s: f64| s < t || (inclusive && s == t);
+        match self {
+            Times::Explicit(ts) => ts[..n].partition_point(|&s| before(s)),
+            Times::Uniform { start, framerate } => {
+                // A guess from the framerate, corrected for rounding.
+                let guess = ((t - start) * *framerate as f64).ceil();
+                let mut k = guess.clamp(0.0, n as f64) as usize;
+                while k > 0 && !before(self.get(k - 1)) {
+                    k -= 1;
+                }
+                while k < n && before(self.get(k)) {
+                    k += 1;
+                }
+                k
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a [f64]> for Times<'a> {
+    fn from(ts: &'a [f64]) -> Self {
+        Times::Explicit(ts)
+    }
+}
+
+/// Named set of samples drawn with a given style.
+#[derive(Debug, Clone, Copy)]
+pub struct Series<'a, T = f32> {
+    pub name: &'a str,
+    pub times: Times<'a>,
+    pub samples: &'a [T],
+    pub style: Style,
+}
+
+impl<'a, T: Sample> Series<'a, T> {
+    /// Creates a series drawn as a line.
+    ///
+    /// # Arguments
+    /// * `name` - name shown in the legend
+    /// * `times` - x coordinates, sorted in ascending order
+    /// * `samples` - y coordinates
+    pub fn new(name: &'a str, times: &'a [f64], samples: &'a [T]) -> Self {
+        Self::with_times(name, Times::Explicit(times), samples)
+    }
+
+    /// Creates a series drawn as a line, e.g. of evenly spaced samples whose
+    /// times don't need to be stored.
+    ///
+    /// # Arguments
+    /// * `name` - name shown in the legend
+    /// * `times` - x coordinates
+    /// * `samples` - y coordinates
+    pub fn with_times(name: &'a str, times: Times<'a>, samples: &'a [T]) -> Self {
+        Self {
+            name,
+            times,
+            samples,
+            style: Style::Line,
+        }
+    }
+
+    /// Sets the style used to draw the series.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Number of points, those with both a time and a sample.
+    fn len(&self) -> usize {
+        match self.times {
+            Times::Uniform { .. } => self.samples.len(),
+            Times::Explicit(ts) => ts.len().min(self.samples.len()),
+        }
+    }
+
+    /// First and last time, if there are any points.
+    fn span(&self) -> Option<(f64, f64)> {
+        match self.len() {
+            0 => None,
+            n => Some((self.times.get(0), self.times.get(n - 1))),
+        }
+    }
+}
+
+/// Plots stacked vertically, each one drawing its own series over a shared time axis.
+///
+/// The summaries of the series are built once, so a frame only reads about
+/// as many samples as there are pixels whatever the zoom.
+struct MyPlot<'a, T> {
+    panels: Vec<Vec<(Series<'a, T>, MinMaxPyramid)>>,
+}
+
+impl<'a, T: Sample> MyPlot<'a, T> {
+    fn new(panels: Vec<&[Series<'a, T>]>) -> Self {
+        let panels = panels
+            .into_iter()
+            .map(|panel| {
+                panel
+                    .iter()
+                    .map(|&series| (series, MinMaxPyramid::new(series.samples)))
+                    .collect()
+            })
+            .collect();
+        Self { panels }
+    }
+}
+
+impl<T: Sample> eframe::App for MyPlot<'_, T> {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let all = self.panels.iter().flatten().map(|(series, _)| series);
+        let Some((first, last)) = time_span(all) else {
+            return;
+        };
         egui::CentralPanel::default().show(ctx, |ui| {
-            Plot::new("My Plot")
-                .legend(Legend::default())
-                .show(ui, |plot_ui| {
-                    plot_ui.line(
-                        Line::new("sin", PlotPoints::Borrowed(&self.plot_points)).name("sin"),
-                    );
-                });
+            let spacing = ui.spacing().item_spacing.y;
+            let n = self.panels.len() as f32;
+            let height = (ui.available_height() - spacing * (n - 1.0)) / n;
+            for (i, panel) in self.panels.iter().enumerate() {
+                Self::panel(ui, i, panel, (first, last), height);
+            }
         });
     }
 }
 
-pub fn render(times: Vec<f32>, samples: Vec<f32>) {
-    env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+impl<T: Sample> MyPlot<'_, T> {
+    fn panel(
+        ui: &mut egui::Ui,
+        i: usize,
+        series: &[(Series<T>, MinMaxPyramid)],
+        span: (f64, f64),
+        height: f32,
+    ) {
+        let (first, last) = span;
+        Plot::new(("My Plot", i))
+            .legend(Legend::default())
+            .height(height)
+            .link_axis("channels", [true, false])
+            .include_x(first)
+            .include_x(last)
+            .show(ui, |plot_ui| {
+                // Only what is visible, at about one min/max pair per pixel.
+                let bounds = plot_ui.plot_bounds();
+                let range = if bounds.is_valid_x() {
+                    (bounds.min()[0], bounds.max()[0])
+                } else {
+                    (first, last)
+                };
+                let buckets = plot_ui.response().rect.width().max(1.0) as usize;
+                for (series, pyramid) in series {
+                    let points = PlotPoints::new(pyramid.decimate(series, range, buckets));
+                    match series.style {
+                        Style::Line => plot_ui.line(Line::new(series.name, points)),
+                        Style::Stems => {
+                            plot_ui.points(Points::new(series.name, points).stems(0.0).radius(2.0))
+                        }
+                        Style::Markers => {
+                            plot_ui.points(Points::new(series.name, points).radius(2.0))
+                        }
+                    }
+                }
+            });
+    }
+}
+
+/// First and last time over all the series.
+fn time_span<'a, T: Sample + 'a>(
+    series: impl IntoIterator<Item = &'a Series<'a, T>>,
+) -> Option<(f64, f64)> {
+    series
+        .into_iter()
+        .filter_map(Series::span)
+        .reduce(|(lo, hi), (first, last)| (lo.min(first), hi.max(last)))
+}
+
+pub fn render<T: Sample>(backend: Backend, series: &[Series<T>]) -> Result<()> {
+    match backend {
+        Backend::Native => render_native(vec![series]),
+        Backend::Terminal { width, height } => {
+            print!("{}", render_terminal(series, width, height));
+            Ok(())
+        }
+    }
+}
+
+/// Draws each series in its own plot, stacked over a shared time axis,
+/// e.g. the channels of a stereo wave.
+///
+/// # Arguments
+/// * `backend` - Where to draw the plots.
+/// * `channels` - One series per plot, from top to bottom.
+///
+/// # Returns
+/// * `Result<()>` - An error if the native window can't be opened.
+pub fn render_channels<T: Sample>(backend: Backend, channels: &[Series<T>]) -> Result<()> {
+    match backend {
+        Backend::Native => render_native(channels.chunks(1).collect()),
+        Backend::Terminal { width, height } => {
+            print!("{}", render_terminal_channels(channels, width, height));
+            Ok(())
+        }
+    }
+}
+
+fn render_native<T: Sample>(panels: Vec<&[Series<T>]>) -> Result<()> {
+    let _ = env_logger::try_init(); // Log to stderr (if you run with `RUST_LOG=debug`).
     log::info!("Starting native…");
 
     let options = eframe::NativeOptions {
@@ -32,19 +301,454 @@ pub fn render(times: Vec<f32>, samples: Vec<f32>) {
         ..Default::default()
     };
 
-    let mut plot_points: Vec<PlotPoint> = Vec::with_capacity(times.len());
-    for i in 0..times.len() {
-        let x = times[i];
-        let y = samples[i];
-        plot_points.push(PlotPoint::new(x, y));
-    }
-
-    if let Err(e) = eframe::run_native(
+    eframe::run_native(
         "My egui App with a plot",
         options,
-        Box::new(|_cc| Ok(Box::new(MyPlot { plot_points }))),
-    ) {
-        println!("Error rendering native: {e}");
-        std::process::exit(1);
+        Box::new(|_cc| Ok(Box::new(MyPlot::new(panels)))),
+    )
+    .map_err(|e| Error::Render(e.to_string()))
+}
+
+/// Min/max decimation of the samples that fall in `range`.
+///
+/// The range is split into `buckets` equal slices of time and each slice is
+/// replaced by its minimum and maximum (in time order), so peaks stay visible
+/// however many samples fall in a slice. The samples just outside the range are
+/// kept so the line reaches the edges. When there are fewer samples than
+/// points to draw, they are returned untouched.
+///
+/// # Arguments
+/// * `times` - x coordinates, sorted in ascending order
+/// * `samples` - y coordinates
+/// * `range` - visible time range `(min, max)`
+/// * `buckets` - number of slices, typically the width in pixels
+///
+/// # Returns
+/// * `Vec<[f64; 2]>` - The points to draw.
+pub fn decimate<T: Sample>(
+    times: &[f64],
+    samples: &[T],
+    range: (f64, f64),
+    buckets: usize,
+) -> Vec<[f64; 2]> {
+    decimate_series(&Series::new("", times, samples), range, buckets)
+}
+
+/// [`decimate`] for any kind of times.
+fn decimate_series<T: Sample>(
+    series: &Series<T>,
+    range: (f64, f64),
+    buckets: usize,
+) -> Vec<[f64; 2]> {
+    let (times, samples) = (series.times, series.samples);
+    let (lo, hi) = visible(series, range);
+    let (t_min, t_max) = range;
+    let buckets = buckets.max(1);
+    if hi - lo <= 2 * buckets || t_max <= t_min {
+        return (lo..hi).map(|i| point(series, i)).collect();
+    }
+
+    let width = (t_max - t_min) / buckets as f64;
+    let bucket_of =
+        |i: usize| (((times.get(i) - t_min) / width).floor().max(-1.0) as i64).min(buckets as i64);
+    let mut points = Vec::with_capacity(2 * buckets + 4);
+    let mut i = lo;
+    while i < hi {
+        let bucket = bucket_of(i);
+        let (mut i_min, mut i_max) = (i, i);
+        let mut j = i + 1;
+        while j < hi && bucket_of(j) == bucket {
+            if samples[j] < samples[i_min] {
+                i_min = j;
+            }
+            if samples[j] > samples[i_max] {
+                i_max = j;
+            }
+            j += 1;
+        }
+        push_extremes(&mut points, series, (i_min, i_max));
+        i = j;
+    }
+    points
+}
+
+/// Indices `lo..hi` of the samples in `range`, plus the one just outside each end.
+fn visible<T: Sample>(series: &Series<T>, range: (f64, f64)) -> (usize, usize) {
+    let n = series.len();
+    let (t_min, t_max) = range;
+    let lo = series.times.count(n, t_min, false).saturating_sub(1);
+    let hi = (series.times.count(n, t_max, true) + 1).min(n);
+    (lo, hi.max(lo))
+}
+
+/// Point of sample `i`.
+fn point<T: Sample>(series: &Series<T>, i: usize) -> [f64; 2] {
+    [series.times.get(i), series.samples[i].into_f64()]
+}
+
+/// Pushes the points of a minimum and a maximum, in time order, once if they are the same.
+fn push_extremes<T: Sample>(
+    points: &mut Vec<[f64; 2]>,
+    series: &Series<T>,
+    extremes: (usize, usize),
+) {
+    let (i_min, i_max) = extremes;
+    points.push(point(series, i_min.min(i_max)));
+    if i_min != i_max {
+        points.push(point(series, i_min.max(i_max)));
+    }
+}
+
+/// Indices of the minimum and maximum of `samples[range]`, which isn't empty.
+fn extremes<T: Sample>(samples: &[T], range: std::ops::Range<usize>) -> (usize, usize) {
+    let start = range.start;
+    range.fold((start, start), |found, i| merge(samples, found, (i, i)))
+}
+
+/// Indices of the minimum and maximum of two sets of samples, from those of each set.
+fn merge<T: Sample>(samples: &[T], a: (usize, usize), b: (usize, usize)) -> (usize, usize) {
+    let i_min = if samples[b.0] < samples[a.0] {
+        b.0
+    } else {
+        a.0
+    };
+    let i_max = if samples[b.1] > samples[a.1] {
+        b.1
+    } else {
+        a.1
+    };
+    (i_min, i_max)
+}
+
+/// Samples in the smallest block of a [`MinMaxPyramid`].
+const BASE_BLOCK: usize = 64;
+
+/// Minimum and maximum of the samples of a series in blocks of every
+/// power-of-two size from [`BASE_BLOCK`] up, so the extremes of any range
+/// are found without scanning it.
+///
+/// Level `k` holds the indices of the minimum and maximum of each block of
+/// `BASE_BLOCK << k` samples, the last one possibly shorter.
+pub(crate) struct MinMaxPyramid {
+    levels: Vec<Vec<(usize, usize)>>,
+}
+
+impl MinMaxPyramid {
+    pub(crate) fn new<T: Sample>(samples: &[T]) -> Self {
+        let n = samples.len();
+        let base: Vec<(usize, usize)> = (0..n)
+            .step_by(BASE_BLOCK)
+            .map(|start| extremes(samples, start..(start + BASE_BLOCK).min(n)))
+            .collect();
+        let mut levels = vec![base];
+        while let Some(last) = levels.last().filter(|level| level.len() > 1) {
+            let merged = last
+                .chunks(2)
+                .map(|pair| pair.iter().copied().reduce(|a, b| merge(samples, a, b)))
+                .collect::<Option<_>>()
+                .unwrap_or_default();
+            levels.push(merged);
+        }
+        Self { levels }
+    }
+
+    /// Min/max decimation of the samples in `range`, like [`decimate`] but
+    /// over blocks of samples rather than slices of time.
+    ///
+    /// The blocks are the largest of the pyramid that still give at least
+    /// `buckets` of them, so at most about `4 * buckets` points are drawn
+    /// and only the partial blocks at both ends are scanned. When the
+    /// blocks would be smaller than [`BASE_BLOCK`], it falls back on [`decimate`].
+    pub(crate) fn decimate<T: Sample>(
+        &self,
+        series: &Series<T>,
+        range: (f64, f64),
+        buckets: usize,
+    ) -> Vec<[f64; 2]> {
+        let (lo, hi) = visible(series, range);
+        let per_bucket = (hi - lo) / buckets.max(1);
+        if per_bucket < 2 * BASE_BLOCK {
+            return decimate_series(series, range, buckets);
+        }
+        let level = (per_bucket / BASE_BLOCK).ilog2() as usize;
+        let block = BASE_BLOCK << level;
+
+        // The samples just outside the range, then the partial block at the
+        // start, the whole blocks and the partial block at the end.
+        let (inner_lo, inner_hi) = (lo + 1, hi - 1);
+        let (first, last) = (inner_lo.div_ceil(block), inner_hi / block);
+        if first >= last {
+            return decimate_series(series, range, buckets);
+        }
+        let mut points = Vec::with_capacity(2 * (last - first) + 6);
+        points.push(point(series, lo));
+        if inner_lo < first * block {
+            push_extremes(
+                &mut points,
+                series,
+                extremes(series.samples, inner_lo..first * block),
+            );
+        }
+        for &block_extremes in &self.levels[level][first..last] {
+            push_extremes(&mut points, series, block_extremes);
+        }
+        if last * block < inner_hi {
+            push_extremes(
+                &mut points,
+                series,
+                extremes(series.samples, last * block..inner_hi),
+            );
+        }
+        points.push(point(series, hi - 1));
+        points
+    }
+}
+
+/// Width of the y axis labels, in characters.
+const Y_LABEL_WIDTH: usize = 9;
+
+/// Draws the series as a braille plot with axis ticks.
+///
+/// All the series share the same axes and are drawn on top of each other.
+///
+/// # Arguments
+/// * `series` - what to draw
+/// * `width` - total width of the plot in characters, labels included
+/// * `height` - number of rows of the plot area
+///
+/// # Returns
+/// * `String` - The plot, one line per row, x axis and labels included.
+pub fn render_terminal<T: Sample>(series: &[Series<T>], width: usize, height: usize) -> String {
+    let cols = width.saturating_sub(Y_LABEL_WIDTH + 1).max(1);
+    let rows = height.max(1);
+    let series: Vec<&Series<T>> = series.iter().filter(|s| s.len() > 0).collect();
+    if series.is_empty() {
+        return "(empty wave)\n".to_string();
+    }
+
+    let (x_min, x_max) = bounds(
+        series
+            .iter()
+            .filter_map(|s| s.span())
+            .flat_map(|(a, b)| [a, b]),
+    );
+    let (y_min, y_max) = bounds(series.iter().flat_map(|s| {
+        let zero = (s.style == Style::Stems).then_some(0.0);
+        s.samples.iter().map(|&y| y.into_f64()).chain(zero)
+    }));
+
+    let mut canvas = BrailleCanvas::new(cols, rows);
+    let (dot_width, dot_height) = (canvas.dot_width(), canvas.dot_height());
+    let to_dot = |t: f64, y: f64| {
+        let x = scale(t, x_min, x_max, dot_width);
+        // Dot rows grow downwards, so the maximum goes to the top.
+        let y = dot_height - 1 - scale(y, y_min, y_max, dot_height);
+        (x, y)
+    };
+    let (_, zero) = to_dot(0.0, 0.0);
+    for s in series {
+        let points = decimate_series(s, (x_min, x_max), dot_width);
+        let mut prev = to_dot(points[0][0], points[0][1]);
+        for p in &points {
+            let next = to_dot(p[0], p[1]);
+            match s.style {
+                Style::Line => canvas.line(prev, next),
+                Style::Stems => canvas.line((next.0, zero), next),
+                Style::Markers => canvas.set(next.0, next.1),
+            }
+            prev = next;
+        }
+    }
+
+    let mut out = String::new();
+    for (row, line) in canvas.rows().enumerate() {
+        let label = if row == 0 {
+            format_tick(y_max, y_max - y_min)
+        } else if row == rows - 1 {
+            format_tick(y_min, y_max - y_min)
+        } else if row == rows / 2 && rows > 2 {
+            format_tick((y_max + y_min) / 2.0, y_max - y_min)
+        } else {
+            String::new()
+        };
+        let tick = if label.is_empty() { '│' } else { '┤' };
+        out.push_str(&format!("{label:>Y_LABEL_WIDTH$}{tick}{line}\n"));
+    }
+
+    // x axis: a tick every 10 columns or so, labels centered under the ticks where they fit.
+    let n_ticks = (cols / 10).clamp(1, 8) + 1;
+    let tick_cols: Vec<usize> = (0..n_ticks)
+        .map(|k| k * (cols - 1) / (n_ticks - 1).max(1))
+        .collect();
+    let mut axis: Vec<char> = vec!['─'; cols];
+    for &c in &tick_cols {
+        axis[c] = '┬';
+    }
+    out.push_str(&format!(
+        "{:>Y_LABEL_WIDTH$}└{}\n",
+        "",
+        axis.into_iter().collect::<String>()
+    ));
+
+    let mut labels: Vec<char> = vec![' '; cols + Y_LABEL_WIDTH + 1];
+    let mut free_from = 0;
+    for &c in &tick_cols {
+        let t = x_min + (x_max - x_min) * c as f64 / (cols - 1).max(1) as f64;
+        let label: Vec<char> = format_tick(t, x_max - x_min).chars().collect();
+        let center = Y_LABEL_WIDTH + 1 + c;
+        let begin = center
+            .saturating_sub(label.len() / 2)
+            .min(labels.len() - label.len().min(labels.len()));
+        if begin < free_from {
+            continue;
+        }
+        for (k, ch) in label.iter().enumerate() {
+            if let Some(slot) = labels.get_mut(begin + k) {
+                *slot = *ch;
+            }
+        }
+        free_from = begin + label.len() + 1;
+    }
+    out.push_str(labels.into_iter().collect::<String>().trim_end());
+    out.push('\n');
+    out
+}
+
+/// Draws each series as its own braille plot, one below the other, each one
+/// headed by the name of the series.
+///
+/// # Arguments
+/// * `channels` - One series per plot, from top to bottom.
+/// * `width` - total width of the plots in characters, labels included
+/// * `height` - number of rows of plot area shared by all the plots, at least 2 each
+///
+/// # Returns
+/// * `String` - The plots, one line per row.
+pub fn render_terminal_channels<T: Sample>(
+    channels: &[Series<T>],
+    width: usize,
+    height: usize,
+) -> String {
+    if channels.is_empty() {
+        return "(empty wave)\n".to_string();
+    }
+    let rows = (height / channels.len()).max(2);
+    let mut out = String::new();
+    for channel in channels {
+        out.push_str(&format!("{:>Y_LABEL_WIDTH$} {}\n", "", channel.name));
+        out.push_str(&render_terminal(std::slice::from_ref(channel), width, rows));
+    }
+    out
+}
+
+/// Min and max of the values, widened when they are all equal so the range is never empty.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    if min > max {
+        (-1.0, 1.0)
+    } else if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+/// Maps `v` from `[min, max]` to a dot index in `0..dots`.
+fn scale(v: f64, min: f64, max: f64, dots: usize) -> usize {
+    let frac = ((v - min) / (max - min)).clamp(0.0, 1.0);
+    if frac.is_nan() {
+        return 0;
+    }
+    ((frac * (dots - 1) as f64).round() as usize).min(dots - 1)
+}
+
+/// Formats a tick label; values negligible compared to the axis `span` print as zero.
+fn format_tick(v: f64, span: f64) -> String {
+    let v = if v.abs() < span * 1e-4 { 0.0 } else { v };
+    let a = v.abs();
+    if a != 0.0 && !(1e-3..1e4).contains(&a) {
+        format!("{v:.1e}")
+    } else if a >= 100.0 {
+        format!("{v:.0}")
+    } else if a >= 1.0 {
+        format!("{v:.2}")
+    } else {
+        format!("{v:.3}")
+    }
+}
+
+/// Grid of braille characters, each one holding 2x4 dots.
+struct BrailleCanvas {
+    cols: usize,
+    rows: usize,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    fn new(cols: usize, rows: usize) -> Self {
+        Self {
+            cols,
+            rows,
+            cells: vec![0; cols * rows],
+        }
+    }
+
+    fn dot_width(&self) -> usize {
+        self.cols * 2
+    }
+
+    fn dot_height(&self) -> usize {
+        self.rows * 4
+    }
+
+    fn set(&mut self, x: usize, y: usize) {
+        if x >= self.dot_width() || y >= self.dot_height() {
+            return;
+        }
+        // Bit layout of the braille block (U+2800):
+        // 0 3
+        // 1 4
+        // 2 5
+        // 6 7
+        const BITS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+        self.cells[(y / 4) * self.cols + x / 2] |= BITS[x % 2][y % 4];
+    }
+
+    /// Bresenham line between two dots.
+    fn line(&mut self, from: (usize, usize), to: (usize, usize)) {
+        let (mut x0, mut y0) = (from.0 as i64, from.1 as i64);
+        let (x1, y1) = (to.0 as i64, to.1 as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x0 as usize, y0 as usize);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.cells.chunks(self.cols).map(|row| {
+            row.iter()
+                .map(|&bits| char::from_u32(0x2800 + bits as u32).unwrap_or(' '))
+                .collect()
+        })
     }
 }
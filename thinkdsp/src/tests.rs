@@ -3,18 +3,25 @@
 // TODO more tests are needed, also check real values (maybe official rust lib for audio https://rust.audio/)
 #[cfg(test)]
 mod book {
-    use crate::book::{CosSignal, Signal, SinSignal, Sinusoid, Wave};
-    use std::f32::consts::PI;
+    use crate::book::{
+        Complex, ComplexSinusoid, ComplexWave, CosSignal, DEFAULT_FRAMERATE, Dither, Envelope,
+        Extension, FmSignal, HarmonicSignal, Impulses, Interpolation, Mix, MultichannelWave,
+        Periodicity, Rounding, Signal, SilentSignal, SinSignal, Sinusoid, Stream, SumSignal,
+        TimeAxis, Wave, Wavetable, bessel_j, dense_framerate, quantization_snr,
+    };
+    use crate::error::Error;
+    use std::f32::consts::{PI, TAU};
 
     // Floating-point precision tolerance constants
     const EPSILON: f32 = 1e-6;
     const EPSILON_LARGE: f32 = 1e-2;
+    const EPSILON_TIME: f64 = 1e-9;
 
     #[test]
     fn signal_default_period() {
         struct TestSignal;
         impl Signal for TestSignal {
-            fn evaluate(&self, _ts: &Vec<f32>) -> Vec<f32> {
+            fn evaluate(&self, _ts: &[f64]) -> Vec<f32> {
                 vec![]
             }
         }
@@ -27,13 +34,13 @@ mod book {
     fn signal_default_make_wave() {
         struct TestSignal;
         impl Signal for TestSignal {
-            fn evaluate(&self, ts: &Vec<f32>) -> Vec<f32> {
-                ts.iter().map(|&t| t * 2.0).collect()
+            fn evaluate(&self, ts: &[f64]) -> Vec<f32> {
+                ts.iter().map(|&t| (t * 2.0) as f32).collect()
             }
         }
 
         let signal = TestSignal;
-        let wave = signal.make_wave(1.0, 0.0, 4);
+        let wave = signal.make_wave(1.0, 0.0, 4).unwrap();
 
         assert_eq!(wave.len(), 4);
         assert_eq!(wave.framerate, 4);
@@ -45,41 +52,241 @@ mod book {
         let ts = vec![0.0, 0.1, 0.2];
         let framerate = 10;
 
-        let wave = Wave::new(ys.clone(), ts.clone(), framerate);
+        let wave = Wave::new(ys.clone(), ts.clone(), framerate).unwrap();
         assert_eq!(wave.len(), 3);
         assert_eq!(wave.framerate, framerate);
     }
 
+    #[test]
+    fn wave_new_rejects_invalid_input() {
+        assert_eq!(
+            Wave::new(vec![1.0f32, 2.0], vec![0.0], 10),
+            Err(Error::LengthMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Wave::new(vec![1.0f32], vec![0.0], 0),
+            Err(Error::ZeroFramerate)
+        );
+        assert!(matches!(
+            Wave::new(vec![1.0f32, 2.0, 3.0], vec![0.0, f64::NAN, 0.3], 10),
+            Err(Error::InvalidTime(t)) if t.is_nan()
+        ));
+        assert_eq!(
+            Wave::new(vec![1.0f32, 2.0, 3.0], vec![0.3, 0.0, 0.1], 10),
+            Err(Error::OutOfRange {
+                name: "decreasing time",
+                value: 0.0
+            })
+        );
+        assert!(
+            ComplexWave::<f32>::new(vec![Complex::new(1.0, 0.0); 2], vec![1.0, 0.5], 10).is_err()
+        );
+        assert!(
+            MultichannelWave::new(vec![vec![1.0f32; 2]], vec![0.0, f64::INFINITY], 10).is_err()
+        );
+    }
+
+    #[test]
+    fn make_wave_rejects_invalid_input() {
+        let s = SinSignal::<f32>::new(1.0, 1.0, 0.0).unwrap();
+        assert_eq!(s.make_wave(1.0, 0.0, 0), Err(Error::ZeroFramerate));
+        assert!(matches!(
+            s.make_wave(f64::NAN, 0.0, 10),
+            Err(Error::InvalidDuration(_))
+        ));
+        assert_eq!(
+            s.make_wave(1.0, f64::INFINITY, 10),
+            Err(Error::InvalidTime(f64::INFINITY))
+        );
+        assert!(s.stream(0.0, 0).is_err());
+    }
+
+    #[test]
+    fn signals_reject_invalid_frequencies() {
+        assert!(matches!(
+            Sinusoid::new(0.0, 1.0f32, 0.0, f32::sin),
+            Err(Error::InvalidFrequency(0.0))
+        ));
+        assert!(CosSignal::<f32>::new(-1.0, 1.0, 0.0).is_err());
+        assert!(HarmonicSignal::<f32>::from_fn(f64::NAN, 3, |_| 1.0).is_err());
+        assert!(FmSignal::<f32>::new(100.0, 1.0, 0.0, 1.0).is_err());
+        assert!(FmSignal::<f32>::new(100.0, 1.0, 5.0, f64::INFINITY).is_err());
+        assert!(ComplexSinusoid::<f32>::new(0.0, 1.0, 0.0).is_err());
+        // Negative frequencies turn clockwise.
+        assert!(ComplexSinusoid::<f32>::new(-100.0, 1.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn operations_reject_invalid_arguments() {
+        let wave = SinSignal::<f32>::new(1.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.0, 8)
+            .unwrap();
+        assert!(wave.sample(0).is_err());
+        assert_eq!(wave.resample(0), Err(Error::ZeroFramerate));
+        assert_eq!(
+            wave.quantize(0),
+            Err(Error::OutOfRange {
+                name: "bits",
+                value: 0.0
+            })
+        );
+        assert!(wave.snr(&Wave::new(vec![], vec![], 8).unwrap()).is_err());
+        let empty = Wave::<f32>::new(vec![], vec![], 8).unwrap();
+        assert_eq!(
+            empty.snr(&empty),
+            Err(Error::OutOfRange {
+                name: "samples",
+                value: 0.0
+            })
+        );
+        assert_eq!(wave.snr(&wave), Ok(f64::INFINITY));
+        assert!(Impulses::uniform(vec![f64::NAN], 1.0f32, 10).is_err());
+        assert!(Envelope::<f32>::adsr(-0.1, 0.1, 0.5, 0.1, 1.0).is_err());
+        assert!(MultichannelWave::from_interleaved(&[1.0f32, 2.0, 3.0], 2, 0.0, 8).is_err());
+        assert!(MultichannelWave::from_interleaved(&[1.0f32], 0, 0.0, 8).is_err());
+        assert!(MultichannelWave::<f32>::from_waves(vec![]).is_err());
+    }
+
     #[test]
     fn wave_len() {
-        let wave = Wave::new(vec![1.0, 2.0, 3.0, 4.0], vec![0.0, 0.1, 0.2, 0.3], 10);
+        let wave = Wave::new(vec![1.0, 2.0, 3.0, 4.0], vec![0.0, 0.1, 0.2, 0.3], 10).unwrap();
         assert_eq!(wave.len(), 4);
     }
 
     #[test]
     fn wave_len_empty() {
-        let wave = Wave::new(vec![], vec![], 10);
+        let wave: Wave = Wave::new(vec![], vec![], 10).unwrap();
         assert_eq!(wave.len(), 0);
     }
 
+    #[test]
+    fn wave_accessors() {
+        let mut wave = Wave::new(vec![1.0f32, 2.0, 3.0], vec![0.5, 0.6, 0.7], 10).unwrap();
+        assert_eq!(wave.ys(), [1.0, 2.0, 3.0]);
+        assert_eq!(wave.ts()[..], [0.5, 0.6, 0.7]);
+        assert_eq!((wave.start(), wave.end()), (0.5, 0.7));
+        assert!((wave.duration() - 0.3).abs() < EPSILON_TIME);
+        assert_eq!(wave[1], 2.0);
+
+        wave[1] = 4.0;
+        wave.ys_mut()[2] *= 2.0;
+        let pairs: Vec<(f64, f32)> = wave.iter().collect();
+        assert_eq!(pairs, [(0.5, 1.0), (0.6, 4.0), (0.7, 6.0)]);
+        assert_eq!((&wave).into_iter().len(), 3);
+
+        let empty: Wave = Wave::new(vec![], vec![], 10).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(
+            (empty.start(), empty.end(), empty.duration()),
+            (0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn wave_collect_and_extend() {
+        let mut wave: Wave = (0..4).map(|i| i as f32).collect();
+        assert_eq!(wave.framerate, DEFAULT_FRAMERATE);
+        assert_eq!(wave.start(), 0.0);
+        assert_eq!(wave.end(), 3.0 / 11025.0);
+
+        wave.extend([4.0, 5.0]);
+        assert_eq!(wave.ys(), [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(wave.end(), 5.0 / 11025.0);
+
+        // Irregular times go on at the framerate.
+        let mut irregular = Wave::new(vec![1.0f32; 2], vec![0.0, 0.5], 10).unwrap();
+        irregular.extend([2.0]);
+        assert_eq!(irregular.ts()[..], [0.0, 0.5, 0.6]);
+    }
+
+    #[test]
+    fn wave_find_index() {
+        let wave = SinSignal::<f32>::new(1.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.5, 10)
+            .unwrap();
+        assert_eq!(wave.find_index(0.5), Some(0));
+        assert_eq!(wave.find_index(0.74), Some(2));
+        assert_eq!(wave.find_index(0.76), Some(3));
+        assert_eq!(wave.find_index(-3.0), Some(0));
+        assert_eq!(wave.find_index(9.0), Some(9));
+        assert_eq!(wave.find_index(f64::NAN), None);
+
+        let irregular = Wave::new(vec![0.0f32; 3], vec![0.0, 0.25, 0.75], 10).unwrap();
+        assert_eq!(irregular.find_index(0.4), Some(1));
+        assert_eq!(irregular.find_index(0.6), Some(2));
+        // Halfway, the later sample, as on the grid.
+        assert_eq!(irregular.find_index(0.5), Some(2));
+
+        let empty: Wave = Wave::new(vec![], vec![], 10).unwrap();
+        assert_eq!(empty.find_index(0.0), None);
+    }
+
+    #[test]
+    fn wave_max_diff_and_assert_close() {
+        let a = Wave::new(vec![1.0f32, 2.0, 3.0], vec![0.0, 0.1, 0.2], 10).unwrap();
+        let b = Wave::new(vec![1.0f32, 2.5, 2.9], vec![0.0, 0.1, 0.2], 10).unwrap();
+        assert_eq!(a.max_diff(&b), Ok(0.5));
+        assert!(a.max_diff(&Wave::new(vec![], vec![], 10).unwrap()).is_err());
+        let nan = Wave::new(vec![f32::NAN, 2.0, 9.0], vec![0.0, 0.1, 0.2], 10).unwrap();
+        assert!(a.max_diff(&nan).unwrap().is_nan());
+        assert!(nan.max_diff(&a).unwrap().is_nan());
+
+        a.assert_close(&b, 0.5);
+        let message = std::panic::catch_unwind(|| a.assert_close(&b, 0.2))
+            .unwrap_err()
+            .downcast::<String>()
+            .unwrap();
+        assert!(message.contains("sample 1 (t = 0.1 s)"), "{message}");
+    }
+
+    #[test]
+    fn wave_time_axis() {
+        // Evenly spaced times are computed, not stored.
+        let ts: Vec<f64> = (0..4).map(|i| 0.5 + i as f64 / 10.0).collect();
+        let wave = Wave::new(vec![1.0f32; 4], ts.clone(), 10).unwrap();
+        assert_eq!(wave.time_axis(), &TimeAxis::Uniform { start: 0.5 });
+        assert_eq!(wave.ts()[..], ts[..]);
+        assert_eq!(wave, Wave::uniform(vec![1.0; 4], 0.5, 10).unwrap());
+
+        let made = SinSignal::<f32>::new(1.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.25, 8)
+            .unwrap();
+        assert_eq!(made.time_axis(), &TimeAxis::Uniform { start: 0.25 });
+        assert_eq!(made.resample(4).unwrap().ts()[1], 0.5);
+
+        // Irregular times are kept as they are.
+        let irregular = Wave::new(vec![1.0f32; 3], vec![0.0, 0.1, 0.3], 10).unwrap();
+        assert_eq!(
+            irregular.time_axis(),
+            &TimeAxis::Explicit(vec![0.0, 0.1, 0.3])
+        );
+        assert_eq!(irregular.ts()[..], [0.0, 0.1, 0.3]);
+    }
+
     #[test]
     fn sinusoid_creation() {
-        let s = Sinusoid::new(440.0, 1.0, 0.0, f32::sin);
+        let s = Sinusoid::new(440.0, 1.0, 0.0, f32::sin).unwrap();
         assert_eq!(s.period(), 1.0 / 440.0);
     }
 
     #[test]
     fn sinusoid_period() {
-        let s = Sinusoid::new(100.0, 1.0, 0.0, f32::sin);
+        let s = Sinusoid::new(100.0, 1.0, 0.0, f32::sin).unwrap();
         assert_eq!(s.period(), 0.01);
 
-        let s2 = Sinusoid::new(50.0, 1.0, 0.0, f32::sin);
+        let s2 = Sinusoid::new(50.0, 1.0, 0.0, f32::sin).unwrap();
         assert_eq!(s2.period(), 0.02);
     }
 
     #[test]
     fn sinusoid_evaluate_sine() {
-        let s = Sinusoid::new(1.0, 1.0, 0.0, f32::sin);
+        let s = Sinusoid::new(1.0, 1.0, 0.0, f32::sin).unwrap();
         let times = vec![0.0, 0.25, 0.5, 0.75, 1.0];
         let result = s.evaluate(&times);
 
@@ -95,7 +302,7 @@ mod book {
 
     #[test]
     fn sinusoid_evaluate_cosine() {
-        let s = Sinusoid::new(1.0, 1.0, 0.0, f32::cos);
+        let s = Sinusoid::new(1.0, 1.0, 0.0, f32::cos).unwrap();
         let times = vec![0.0, 0.25, 0.5, 0.75, 1.0];
         let result = s.evaluate(&times);
 
@@ -111,7 +318,7 @@ mod book {
 
     #[test]
     fn sinusoid_evaluate_with_amplitude() {
-        let s = Sinusoid::new(1.0, 2.0, 0.0, f32::sin);
+        let s = Sinusoid::new(1.0, 2.0, 0.0, f32::sin).unwrap();
         let times = vec![0.25]; // sin(π/2) = 1, so 2 * 1 = 2
         let result = s.evaluate(&times);
         assert!((result[0] - 2.0).abs() < EPSILON);
@@ -119,7 +326,7 @@ mod book {
 
     #[test]
     fn sinusoid_evaluate_with_offset() {
-        let s = Sinusoid::new(1.0, 1.0, PI / 2.0, f32::sin);
+        let s = Sinusoid::new(1.0, 1.0, PI / 2.0, f32::sin).unwrap();
         let times = vec![0.0]; // sin(π/2) = 1
         let result = s.evaluate(&times);
         assert!((result[0] - 1.0).abs() < EPSILON);
@@ -127,22 +334,108 @@ mod book {
 
     #[test]
     fn sinusoid_make_wave() {
-        let s = Sinusoid::new(440.0, 1.0, 0.0, f32::sin);
-        let wave = s.make_wave(1.0, 0.0, 44100);
+        let s = Sinusoid::new(440.0, 1.0, 0.0, f32::sin).unwrap();
+        let wave = s.make_wave(1.0, 0.0, 44100).unwrap();
         assert_eq!(wave.len(), 44100);
     }
 
+    #[test]
+    fn sinusoid_make_wave_hour_long() {
+        // The last 50 ms of an hour of a 440 Hz sine at 44.1 kHz.
+        let (freq, framerate): (u64, u64) = (440, 44100);
+        let n = framerate / 20;
+        let first = 3600 * framerate - n;
+
+        let s = SinSignal::<f32>::new(freq as f64, 1.0, 0.0).unwrap();
+        let duration = n as f64 / framerate as f64;
+        let start = first as f64 / framerate as f64;
+        let wave = s.make_wave(duration, start, framerate).unwrap();
+        assert_eq!(wave.len(), n as usize);
+
+        for (i, (&t, &y)) in wave.ts().iter().zip(wave.ys()).enumerate() {
+            let k = first + i as u64;
+            // Analytic reference: the whole cycles are dropped in integer arithmetic.
+            let cycles = ((freq * k) % framerate) as f64 / framerate as f64;
+            let expected = (std::f64::consts::TAU * cycles).sin();
+
+            assert!((t - k as f64 / framerate as f64).abs() < EPSILON_TIME);
+            assert!(
+                (y as f64 - expected).abs() < 1e-5,
+                "sample {k}: {y} != {expected}"
+            );
+        }
+    }
+
     #[test]
     fn sinusoid_make_wave_with_start() {
-        let s = Sinusoid::new(1.0, 1.0, 0.0, f32::sin);
-        let wave = s.make_wave(1.0, 0.5, 4);
+        let s = Sinusoid::new(1.0, 1.0, 0.0, f32::sin).unwrap();
+        let wave = s.make_wave(1.0, 0.5, 4).unwrap();
         assert_eq!(wave.len(), 4);
         assert_eq!(wave.framerate, 4);
     }
 
+    #[test]
+    fn sinusoid_closure_with_state() {
+        let duty = 0.25;
+        let pulse = Sinusoid::new(1.0, 1.0, 0.0, move |phase: f32| {
+            if phase.rem_euclid(TAU) < duty * TAU {
+                1.0
+            } else {
+                -1.0
+            }
+        })
+        .unwrap();
+        assert_eq!(pulse.period(), 1.0);
+        assert_eq!(
+            pulse.evaluate(&[0.1, 0.3, 0.6, 1.1]),
+            vec![1.0, -1.0, -1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn signals_are_send_and_sync() {
+        fn send_sync<S: Send + Sync>(_: &S) {}
+
+        let sin = SinSignal::<f32>::new(1.0, 1.0, 0.0).unwrap();
+        send_sync(&sin);
+        send_sync(&CosSignal::<f64>::new(1.0, 1.0, 0.0).unwrap());
+        send_sync(&Sinusoid::new(1.0, 1.0, 0.0, f32::sin).unwrap());
+        send_sync(&Mix::new().with(0.5, sin));
+    }
+
+    #[test]
+    fn wavetable_lookup() {
+        let table = Wavetable::new(vec![0.0, 1.0, 0.0, -1.0]);
+        assert_eq!(table.lookup(0.0), 0.0);
+        assert_eq!(table.lookup(PI / 2.0), 1.0);
+        // Halfway between two entries, and wrapping around the end of the cycle.
+        assert!((table.lookup(PI / 4.0) - 0.5).abs() < EPSILON);
+        assert!((table.lookup(7.0 * PI / 4.0) - (-0.5)).abs() < EPSILON);
+        assert!((table.lookup(-PI / 2.0) - (-1.0)).abs() < EPSILON);
+        assert_eq!(Wavetable::new(vec![]).lookup(1.0), 0.0);
+    }
+
+    #[test]
+    fn sinusoid_from_wavetable() {
+        // One cycle of a sine, sampled from a wave.
+        let cycle = SinSignal::new(1.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.0, 256)
+            .unwrap();
+        let s = Sinusoid::from_wavetable(100.0, 2.0, 0.0, Wavetable::from(&cycle)).unwrap();
+        assert_eq!(s.period(), 1.0 / 100.0);
+
+        let times = vec![0.0, 0.0025, 0.0031, 0.0125];
+        let expected: Vec<f32> = SinSignal::new(100.0, 2.0, 0.0).unwrap().evaluate(&times);
+        let result: Vec<f32> = s.evaluate(&times);
+        for (r, e) in result.iter().zip(&expected) {
+            assert!((r - e).abs() < EPSILON_LARGE);
+        }
+    }
+
     #[test]
     fn cos_signal_from_conversion() {
-        let cos = CosSignal::new(100.0, 2.0, PI / 4.0);
+        let cos = CosSignal::new(100.0, 2.0, PI / 4.0).unwrap();
         let sinusoid: Sinusoid = cos.into();
 
         // Test that the conversion preserves the properties
@@ -156,7 +449,7 @@ mod book {
 
     #[test]
     fn sin_signal_from_conversion() {
-        let sin = SinSignal::new(100.0, 2.0, PI / 4.0);
+        let sin = SinSignal::new(100.0, 2.0, PI / 4.0).unwrap();
         let sinusoid: Sinusoid = sin.into();
 
         // Test that the conversion preserves the properties
@@ -170,17 +463,17 @@ mod book {
 
     #[test]
     fn sum_signal_as_result_of_sinusoid_add() {
-        let s1 = Sinusoid::new(440.0, 1.0, 0.0, f32::sin);
-        let s2 = Sinusoid::new(880.0, 0.5, 0.0, f32::sin);
+        let s1 = Sinusoid::new(440.0, 1.0, 0.0, f32::sin).unwrap();
+        let s2 = Sinusoid::new(880.0, 0.5, 0.0, f32::sin).unwrap();
 
         let sum = s1 + s2;
-        assert_eq!(sum.period(), 1.0 / 440.0); // max of the two periods
+        assert_eq!(sum.period(), 1.0 / 440.0); // fundamental of 440 and 880 Hz
     }
 
     #[test]
     fn sum_signal_evaluate() {
-        let s1 = Sinusoid::new(1.0, 1.0, 0.0, f32::sin);
-        let s2 = Sinusoid::new(1.0, 2.0, 0.0, f32::cos);
+        let s1 = Sinusoid::new(1.0, 1.0, 0.0, f32::sin).unwrap();
+        let s2 = Sinusoid::new(1.0, 2.0, 0.0, f32::cos).unwrap();
         let sum = s1 + s2;
 
         let times = vec![0.0, 0.25, 0.5];
@@ -196,12 +489,1360 @@ mod book {
 
     #[test]
     fn sum_signal_make_wave() {
-        let s1 = Sinusoid::new(1.0, 1.0, 0.0, f32::sin);
-        let s2 = Sinusoid::new(1.0, 1.0, 0.0, f32::cos);
+        let s1 = Sinusoid::new(1.0, 1.0, 0.0, f32::sin).unwrap();
+        let s2 = Sinusoid::new(1.0, 1.0, 0.0, f32::cos).unwrap();
         let sum = s1 + s2;
 
-        let wave = sum.make_wave(1.0, 0.0, 4);
+        let wave = sum.make_wave(1.0, 0.0, 4).unwrap();
         assert_eq!(wave.len(), 4);
         assert_eq!(wave.framerate, 4);
     }
+
+    #[test]
+    fn sum_signal_of_different_types() {
+        let cos = CosSignal::new(1.0, 2.0, 0.0).unwrap();
+        let sin = SinSignal::new(1.0, 1.0, 0.0).unwrap();
+        let sum: SumSignal<CosSignal, SinSignal> = cos + sin;
+
+        let result = sum.evaluate(&[0.0, 0.25]);
+        assert!((result[0] - 2.0).abs() < EPSILON);
+        assert!((result[1] - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn sum_signal_chained() {
+        let sum = CosSignal::new(1.0, 1.0, 0.0).unwrap()
+            + SinSignal::new(1.0, 1.0, 0.0).unwrap()
+            + Sinusoid::new(2.0, 1.0, 0.0, f32::cos).unwrap();
+
+        // At t=0.25: cos(π/2) + sin(π/2) + cos(π) = 0 + 1 - 1 = 0
+        let result = sum.evaluate(&[0.0, 0.25]);
+        assert!((result[0] - 2.0).abs() < EPSILON);
+        assert!((result[1] - 0.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn sum_signal_boxed() {
+        struct Ramp;
+        impl Signal for Ramp {
+            fn evaluate(&self, ts: &[f64]) -> Vec<f32> {
+                ts.iter().map(|&t| t as f32).collect()
+            }
+        }
+
+        let signals: Vec<Box<dyn Signal>> =
+            vec![Ramp.boxed(), CosSignal::new(1.0, 1.0, 0.0).unwrap().boxed()];
+        let mut signals = signals.into_iter();
+        let sum = signals.next().unwrap() + signals.next().unwrap();
+
+        let result = sum.evaluate(&[0.0, 0.5]);
+        assert!((result[0] - 1.0).abs() < EPSILON);
+        assert!((result[1] - (0.5 - 1.0)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn sum_signal_fundamental_period() {
+        // 440 and 660 Hz share a 220 Hz fundamental.
+        let sum =
+            CosSignal::new(440.0, 1.0, 0.0).unwrap() + CosSignal::new(660.0, 1.0, 0.0).unwrap();
+        assert_eq!(sum.periodicity(), Periodicity::Periodic(1.0 / 220.0));
+        assert!((sum.period() - 1.0 / 220.0).abs() < EPSILON_TIME);
+
+        // Beating tones repeat once per second.
+        let beats =
+            CosSignal::new(440.0, 1.0, 0.0).unwrap() + CosSignal::new(441.0, 1.0, 0.0).unwrap();
+        assert!((beats.period() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sum_signal_aperiodic() {
+        let inharmonic = CosSignal::new(440.0, 1.0, 0.0).unwrap()
+            + CosSignal::new(440.0 * std::f64::consts::SQRT_2, 1.0, 0.0).unwrap();
+        assert_eq!(inharmonic.periodicity(), Periodicity::Aperiodic);
+        // Plotting falls back on the longest period of the components.
+        assert_eq!(inharmonic.period(), 1.0 / 440.0);
+    }
+
+    #[test]
+    fn sum_signal_constant_component() {
+        // A 0 Hz cosine is rejected, a constant takes its place.
+        let sum = CosSignal::new(100.0, 1.0, 0.0).unwrap() + 1.0f32;
+        assert_eq!(sum.periodicity(), Periodicity::Periodic(1.0 / 100.0));
+    }
+
+    #[test]
+    fn periodicity_combine_within() {
+        let a = Periodicity::Periodic(1.0 / 300.0);
+        let b = Periodicity::Periodic(1.0 / 500.0);
+        assert_eq!(
+            a.combine_within(b, 1e-4, 1.0)
+                .period()
+                .map(|p| (p * 100.0).round()),
+            Some(1.0)
+        );
+        // The fundamental is 100 Hz, too long a period for this bound.
+        assert_eq!(a.combine_within(b, 1e-4, 0.005), Periodicity::Aperiodic);
+        assert_eq!(a.combine(Periodicity::Aperiodic), Periodicity::Aperiodic);
+    }
+
+    #[test]
+    fn periodicity_combine_is_symmetric() {
+        let short = Periodicity::Periodic(1e-4);
+        let long = Periodicity::Periodic(1.0);
+        assert_eq!(short.combine(long), Periodicity::Periodic(1.0));
+        assert_eq!(long.combine(short), Periodicity::Periodic(1.0));
+
+        let cos = |freq| CosSignal::<f32>::new(freq, 1.0, 0.0).unwrap();
+        assert!(((cos(20000.0) + cos(1.0)).period() - 1.0).abs() < EPSILON_TIME);
+        assert!(((cos(1.0) + cos(20000.0)).period() - 1.0).abs() < EPSILON_TIME);
+    }
+
+    #[test]
+    fn product_signal_ring_modulation() {
+        let carrier = CosSignal::new(4.0, 1.0, 0.0).unwrap();
+        let modulator = CosSignal::new(1.0, 1.0, 0.0).unwrap();
+        let ring = carrier * modulator;
+
+        // cos(a) cos(b) = (cos(a - b) + cos(a + b)) / 2
+        let expected: Vec<f32> = (CosSignal::new(3.0, 0.5, 0.0).unwrap()
+            + CosSignal::new(5.0, 0.5, 0.0).unwrap())
+        .evaluate(&[0.0, 0.1, 0.37]);
+        let result: Vec<f32> = ring.evaluate(&[0.0, 0.1, 0.37]);
+        for (r, e) in result.iter().zip(&expected) {
+            assert!((r - e).abs() < EPSILON);
+        }
+        assert!((ring.period() - 1.0).abs() < EPSILON_TIME);
+    }
+
+    #[test]
+    fn scaled_and_negated_signal() {
+        let scaled = SinSignal::<f32>::new(1.0, 1.0, 0.0).unwrap() * 3.0;
+        assert!((scaled.evaluate(&[0.25])[0] - 3.0).abs() < EPSILON);
+        assert_eq!(scaled.period(), 1.0);
+
+        let negated = -SinSignal::<f32>::new(1.0, 1.0, 0.0).unwrap().scale(2.0);
+        assert!((negated.evaluate(&[0.25])[0] - (-2.0)).abs() < EPSILON);
+
+        let silence =
+            CosSignal::<f32>::new(3.0, 1.0, 0.0).unwrap() - CosSignal::new(3.0, 1.0, 0.0).unwrap();
+        assert!(
+            silence
+                .evaluate(&[0.0, 0.1, 0.2])
+                .iter()
+                .all(|y| y.abs() < EPSILON)
+        );
+        assert!((silence.period() - 1.0 / 3.0).abs() < EPSILON_TIME);
+    }
+
+    #[test]
+    fn delayed_signal() {
+        let delayed = SinSignal::<f32>::new(1.0, 1.0, 0.0)
+            .unwrap()
+            .delay(0.25)
+            .unwrap();
+        // sin(2π (t - 0.25)) = -cos(2π t)
+        let result = delayed.evaluate(&[0.0, 0.5]);
+        assert!((result[0] - (-1.0)).abs() < EPSILON);
+        assert!((result[1] - 1.0).abs() < EPSILON);
+        assert_eq!(delayed.period(), 1.0);
+
+        let sin = || SinSignal::<f32>::new(1.0, 1.0, 0.0).unwrap();
+        assert!(matches!(
+            sin().delay(f64::NAN),
+            Err(Error::InvalidTime(t)) if t.is_nan()
+        ));
+        assert!(matches!(
+            sin().delay(f64::NEG_INFINITY),
+            Err(Error::InvalidTime(f64::NEG_INFINITY))
+        ));
+    }
+
+    #[test]
+    fn time_scaled_signal() {
+        let faster = CosSignal::new(100.0, 1.0, 0.0)
+            .unwrap()
+            .time_scale(2.0)
+            .unwrap();
+        assert_eq!(faster.period(), 1.0 / 200.0);
+        let expected: Vec<f32> = CosSignal::new(200.0, 1.0, 0.0)
+            .unwrap()
+            .evaluate(&[0.001, 0.0042]);
+        let result: Vec<f32> = faster.evaluate(&[0.001, 0.0042]);
+        for (r, e) in result.iter().zip(&expected) {
+            assert!((r - e).abs() < EPSILON_LARGE);
+        }
+
+        let slower = CosSignal::new(100.0, 1.0, 0.0)
+            .unwrap()
+            .time_scale(0.5)
+            .unwrap();
+        assert_eq!(slower.period(), 1.0 / 50.0);
+
+        let cos = || CosSignal::<f32>::new(100.0, 1.0, 0.0).unwrap();
+        assert!(matches!(
+            cos().time_scale(f64::INFINITY),
+            Err(Error::OutOfRange { name: "factor", .. })
+        ));
+        assert!(cos().time_scale(f64::NAN).is_err());
+        assert_eq!(
+            cos().time_scale(0.0).err(),
+            Some(Error::OutOfRange {
+                name: "factor",
+                value: 0.0
+            })
+        );
+        let backwards = cos().time_scale(-2.0).unwrap();
+        assert_eq!(backwards.period(), 1.0 / 200.0);
+    }
+
+    #[test]
+    fn combinators_compose_with_sums() {
+        let am = (CosSignal::new(1.0, 1.0, 0.0).unwrap() * 0.5
+            + Mix::new().with(1.0, SinSignal::new(2.0, 1.0, 0.0).unwrap()))
+            * CosSignal::new(4.0, 1.0, 0.0).unwrap().delay(0.125).unwrap();
+        assert!((am.period() - 1.0).abs() < EPSILON_TIME);
+        assert_eq!(am.make_wave(1.0, 0.0, 8).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn mix_evaluate() {
+        let mix = Mix::new()
+            .with(0.5, CosSignal::new(1.0, 1.0, 0.0).unwrap())
+            .with(2.0, SinSignal::new(1.0, 1.0, 0.0).unwrap())
+            .with(1.0, Sinusoid::new(2.0, 1.0, 0.0, f32::cos).unwrap());
+        assert_eq!(mix.len(), 3);
+
+        assert!((mix.period() - 1.0).abs() < EPSILON_TIME);
+
+        // At t=0: 0.5 * 1 + 2 * 0 + 1 = 1.5
+        // At t=0.25: 0.5 * 0 + 2 * 1 - 1 = 1
+        let result = mix.evaluate(&[0.0, 0.25]);
+        assert!((result[0] - 1.5).abs() < EPSILON);
+        assert!((result[1] - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn mix_from_iter() {
+        let mix: Mix = (1..=3)
+            .map(|k| (1.0 / k as f32, CosSignal::new(k as f64, 1.0, 0.0).unwrap()))
+            .collect();
+        assert_eq!(mix.len(), 3);
+        assert!((mix.period() - 1.0).abs() < EPSILON_TIME);
+
+        let result = mix.evaluate(&[0.0]);
+        assert!((result[0] - (1.0 + 0.5 + 1.0 / 3.0)).abs() < EPSILON);
+
+        assert_eq!(Mix::<f32>::new().evaluate(&[0.0, 1.0]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn f64_signal_precision() {
+        let cos = CosSignal::<f64>::new(440.0, 1.0, 0.0).unwrap();
+        let times = [0.0, 1.0 / 1760.0, 1.0 / 880.0];
+        let result = cos.evaluate(&times);
+        for (r, e) in result.iter().zip(&[1.0, 0.0, -1.0]) {
+            assert!((r - e).abs() < 1e-12);
+        }
+
+        let wave: Wave<f64> = (cos + SinSignal::new(880.0, 0.5, 0.0).unwrap())
+            .make_wave(0.5, 0.0, 44100)
+            .unwrap();
+        assert_eq!(wave.len(), 22050);
+    }
+
+    #[test]
+    fn f64_sinusoid_with_closure() {
+        let square = Sinusoid::new(1.0, 2.0, 0.0, |phase: f64| phase.sin().signum()).unwrap();
+        assert_eq!(square.evaluate(&[0.25, 0.75]), vec![2.0, -2.0]);
+
+        let mix: Mix<f64> = Mix::new().with(0.5, square).with(1.0, 1.0);
+        assert_eq!(mix.evaluate(&[0.25]), vec![2.0]);
+    }
+
+    #[test]
+    fn complex_sinusoid_projections() {
+        let signal = ComplexSinusoid::<f32>::new(5.0, 2.0, PI / 4.0).unwrap();
+        let wave = signal.make_wave(1.0, 0.0, 100).unwrap();
+        assert_eq!(wave.len(), 100);
+        assert_eq!(signal.period(), 0.2);
+
+        let real = signal.real().make_wave(1.0, 0.0, 100).unwrap();
+        let imag = signal.imag().make_wave(1.0, 0.0, 100).unwrap();
+        assert_eq!(wave.real().ys(), real.ys());
+        assert_eq!(wave.imag().ys(), imag.ys());
+        assert!(wave.abs().ys().iter().all(|m| (m - 2.0).abs() < EPSILON));
+        assert!((wave.angle().ys()[0] - PI / 4.0).abs() < EPSILON);
+        assert_eq!(signal.angle(&[0.0]), vec![PI / 4.0]);
+    }
+
+    #[test]
+    fn complex_sinusoid_conjugate() {
+        let signal = ComplexSinusoid::<f64>::new(3.0, 1.0, 0.5).unwrap();
+        let ts = [0.0, 0.01, 0.123];
+        let conj = signal.conj().evaluate(&ts);
+        for (c, y) in conj.iter().zip(signal.evaluate(&ts)) {
+            assert!((c - y.conj()).norm() < 1e-12);
+        }
+
+        let wave = signal.make_wave(0.5, 0.0, 50).unwrap();
+        assert_eq!(wave.conj().imag().ys()[3], -wave.imag().ys()[3]);
+        assert_eq!(wave.conj().real().ys(), wave.real().ys());
+
+        // A real wave lifted to complex has no imaginary part.
+        let lifted = ComplexWave::from(&wave.real());
+        assert!(lifted.ys().iter().all(|y| y.im == 0.0));
+        assert_eq!(lifted.ys()[0], Complex::new(0.5f64.cos(), 0.0));
+    }
+
+    #[test]
+    fn silent_signal() {
+        let silence: Vec<f32> = SilentSignal.evaluate(&[0.0, 0.5, 1.0]);
+        assert_eq!(silence, vec![0.0; 3]);
+
+        let sum = CosSignal::<f32>::new(100.0, 1.0, 0.0).unwrap() + SilentSignal;
+        assert!((sum.period() - 0.01).abs() < EPSILON_TIME);
+        assert_eq!(
+            sum.evaluate(&[0.0, 0.0025]),
+            CosSignal::new(100.0, 1.0, 0.0)
+                .unwrap()
+                .evaluate(&[0.0, 0.0025])
+        );
+    }
+
+    #[test]
+    fn impulses_evaluate() {
+        let impulses = Impulses::new(vec![0.0, 0.25, 0.61], vec![1.0, -0.5, 2.0], 10).unwrap();
+        assert_eq!(impulses.periodicity(), Periodicity::Aperiodic);
+
+        let wave = impulses.make_wave(1.0, 0.0, 10).unwrap();
+        // An impulse between samples lands on the next one.
+        assert_eq!(
+            wave.ys(),
+            [1.0, 0.0, 0.0, -0.5, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0]
+        );
+
+        // Evaluated in blocks of any size, each impulse shows up once.
+        for size in [1, 3, 10] {
+            let mut streamed = vec![0.0; 10];
+            let mut stream = impulses.stream(0.0, 10).unwrap();
+            for block in streamed.chunks_mut(size) {
+                stream.fill(block);
+            }
+            assert_eq!(streamed, wave.ys(), "blocks of {size}");
+        }
+
+        // Between samples, in single-sample blocks too.
+        let between = Impulses::uniform(vec![0.05], 1.0f32, 10).unwrap();
+        let mut stream = between.stream(0.0, 10).unwrap();
+        let total: f32 = (0..10)
+            .map(|_| {
+                let mut block = [0.0];
+                stream.fill(&mut block);
+                block[0]
+            })
+            .sum();
+        assert_eq!(total, 1.0);
+    }
+
+    #[test]
+    fn plot_over_grid_is_bounded() {
+        // 16 times the framerate, at least 2000 points and at most about a million.
+        assert_eq!(dense_framerate(1000, 1.0), 16000);
+        assert_eq!(dense_framerate(10, 1.0), 2000);
+        let long = dense_framerate(44100, 600.0);
+        assert!(long as f64 * 600.0 <= (1 << 20) as f64);
+        assert!(long > 0);
+    }
+
+    #[test]
+    fn wave_sample_with_impulses() {
+        let wave = CosSignal::<f32>::new(1.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.0, 8)
+            .unwrap();
+        let sampled = wave.sample(4).unwrap();
+        assert_eq!(sampled.len(), wave.len());
+        assert_eq!(sampled.ys(), [1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(sampled.ts(), wave.ts());
+
+        // Every `factor`-th sample is kept whatever the times.
+        let irregular = Wave::new(vec![1.0f32; 5], vec![0.0, 0.1, 0.35, 0.4, 0.9], 10).unwrap();
+        assert_eq!(irregular.sample(2).unwrap().ys(), [1.0, 0.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn envelope_breakpoints() {
+        let envelope = Envelope::new(vec![(1.0, 0.5), (0.0, 0.0), (2.0, 0.5)]).unwrap();
+        assert_eq!(
+            envelope.evaluate(&[-1.0, 0.0, 0.5, 1.0, 1.5, 3.0]),
+            vec![0.0, 0.0, 0.25, 0.5, 0.5, 0.5]
+        );
+        assert_eq!(envelope.period(), 2.0);
+        assert_eq!(Envelope::<f32>::new(vec![]).unwrap().level(1.0), 1.0);
+    }
+
+    #[test]
+    fn envelope_adsr_note() {
+        let envelope = Envelope::<f32>::adsr(0.1, 0.1, 0.5, 0.2, 1.0).unwrap();
+        let levels = envelope.evaluate(&[0.0, 0.05, 0.1, 0.2, 0.5, 0.8, 0.9, 1.0, 1.5]);
+        let expected = [0.0, 0.5, 1.0, 0.5, 0.5, 0.5, 0.25, 0.0, 0.0];
+        for (l, e) in levels.iter().zip(&expected) {
+            assert!((l - e).abs() < EPSILON);
+        }
+
+        // The note starts and ends without a click.
+        let note = envelope.apply(CosSignal::new(440.0, 1.0, 0.0).unwrap());
+        assert_eq!(note.periodicity(), Periodicity::Aperiodic);
+        let wave = note.make_wave(1.0, 0.0, 11025).unwrap();
+        assert_eq!(wave.ys()[0], 0.0);
+        assert!(wave.ys()[wave.len() - 1].abs() < 1e-3);
+
+        let mut raw = CosSignal::new(440.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.0, 11025)
+            .unwrap();
+        raw.apply_envelope(&Envelope::adsr(0.1, 0.1, 0.5, 0.2, 1.0).unwrap());
+        assert_eq!(raw.ys(), wave.ys());
+    }
+
+    #[test]
+    fn wave_apodize() {
+        let mut wave = 1.0f32.make_wave(1.0, 0.0, 100).unwrap();
+        wave.apodize(20, 0.1);
+        let ys = wave.ys();
+        // 5 samples are tapered at each end, from 0 to 1.
+        assert_eq!(&ys[..5], [0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(&ys[95..], [1.0, 0.75, 0.5, 0.25, 0.0]);
+        assert!(ys[5..95].iter().all(|&y| y == 1.0));
+
+        // The ramps don't overlap, even when `denom` would allow it.
+        let mut short = 1.0f32.make_wave(3.0, 0.0, 1).unwrap();
+        short.apodize(1, 10.0);
+        assert_eq!(short.ys(), [0.0, 1.0, 0.0]);
+        let mut even = 1.0f32.make_wave(4.0, 0.0, 1).unwrap();
+        even.apodize(1, 10.0);
+        assert_eq!(even.ys(), [0.0, 1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn harmonic_signal_table() {
+        let signal = HarmonicSignal::new(
+            100.0,
+            vec![(1, 1.0, 0.0), (3, 0.5, PI / 2.0), (0, 0.25, PI / 2.0)],
+        )
+        .unwrap();
+        assert_eq!(signal.len(), 3);
+        assert_eq!(signal.period(), 0.01);
+        assert_eq!(signal.periodicity(), Periodicity::Periodic(0.01));
+
+        let ts = [0.0, 0.0012, 0.0047];
+        let expected = (SinSignal::new(100.0, 1.0, 0.0).unwrap()
+            + CosSignal::new(300.0, 0.5, 0.0).unwrap()
+            + 0.25)
+            .evaluate(&ts);
+        for (y, e) in signal.evaluate(&ts).iter().zip(&expected) {
+            assert!((y - e).abs() < EPSILON * 10.0);
+        }
+    }
+
+    #[test]
+    fn harmonic_signal_sawtooth() {
+        // sin(x) - sin(2x)/2 + sin(3x)/3 - ... converges to x/2 on (-π, π).
+        let saw = HarmonicSignal::<f64>::from_fn(5.0, 2000, |n| {
+            let sign = if n % 2 == 1 { 1.0 } else { -1.0 };
+            sign / n as f64
+        })
+        .unwrap();
+        assert_eq!(saw.len(), 2000);
+        assert!((saw.period() - 0.2).abs() < EPSILON_TIME);
+        // x = π/2 at a quarter period.
+        let y = saw.evaluate(&[0.05])[0];
+        assert!((y - std::f64::consts::FRAC_PI_4).abs() < 1e-3);
+    }
+
+    #[test]
+    fn bessel_j_values() {
+        assert!((bessel_j(0, 1.0) - 0.765_197_686_6).abs() < 1e-9);
+        assert!((bessel_j(1, 1.0) - 0.440_050_585_7).abs() < 1e-9);
+        assert!((bessel_j(-1, 1.0) + 0.440_050_585_7).abs() < 1e-9);
+        assert!((bessel_j(3, 5.0) - 0.364_831_230_6).abs() < 1e-9);
+        assert!(bessel_j(0, 2.404_825_557_695_773).abs() < 1e-9);
+        assert!(bessel_j(2, 0.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn fm_signal_sidebands() {
+        // 64 samples over a second, so every component falls on a DFT bin.
+        let fm = FmSignal::<f64>::new(16.0, 1.0, 3.0, 1.5).unwrap();
+        assert!((fm.period() - 1.0).abs() < EPSILON_TIME);
+
+        let wave = fm.make_wave(1.0, 0.0, 64).unwrap();
+        let spectrum = crate::book::dft::dft(ComplexWave::from(&wave).ys());
+        for (freq, amp) in fm.sidebands(4) {
+            // A sine of amplitude `a` shows up as `N a / 2` in its bin.
+            let bin = spectrum[freq as usize].norm() / 32.0;
+            assert!((bin - amp.abs()).abs() < 1e-6, "{freq} Hz: {bin} != {amp}");
+        }
+    }
+
+    #[test]
+    fn fm_signal_stacking() {
+        let ts = [0.0, 0.013, 0.25, 0.7];
+        let plain = FmSignal::<f64>::new(100.0, 1.0, 50.0, 2.0).unwrap();
+        let stacked = plain.clone().stack(7.0, 0.0).unwrap();
+        assert_eq!(stacked.evaluate(&ts), plain.evaluate(&ts));
+        assert_eq!(stacked.periodicity(), plain.periodicity());
+
+        // Without modulation it is a plain sine.
+        let sine = FmSignal::<f64>::new(100.0, 1.0, 50.0, 0.0)
+            .unwrap()
+            .evaluate(&ts);
+        for (y, e) in sine
+            .iter()
+            .zip(SinSignal::new(100.0, 1.0, 0.0).unwrap().evaluate(&ts))
+        {
+            assert!((y - e).abs() < 1e-12);
+        }
+
+        let stacked = plain.stack(5.0, 1.0).unwrap();
+        assert!((stacked.period() - 0.2).abs() < EPSILON_TIME);
+        // sin(2π 100 t + 2 sin(2π 50 t + sin(2π 5 t)))
+        let t: f64 = 0.013;
+        let inner = (std::f64::consts::TAU * 5.0 * t).sin();
+        let middle = 2.0 * (std::f64::consts::TAU * 50.0 * t + inner).sin();
+        let expected = (std::f64::consts::TAU * 100.0 * t + middle).sin();
+        assert!((stacked.evaluate(&[t])[0] - expected).abs() < 1e-12);
+    }
+
+    /// Largest difference between two waves, away from their edges.
+    fn max_error(a: &Wave<f64>, b: &Wave<f64>, margin: usize) -> f64 {
+        a.ys()[margin..a.len() - margin]
+            .iter()
+            .zip(&b.ys()[margin..])
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn wave_resample_down_and_up() {
+        let signal = CosSignal::<f64>::new(440.0, 1.0, 0.3).unwrap();
+        let wave = signal.make_wave(0.25, 0.5, 44100).unwrap();
+
+        let down = wave.resample(11025).unwrap();
+        assert_eq!(down.framerate, 11025);
+        assert_eq!(down.len(), 2756);
+        assert_eq!(down.ts()[0], 0.5);
+        assert!((down.ts()[1] - (0.5 + 1.0 / 11025.0)).abs() < EPSILON_TIME);
+        let expected = signal.make_wave(0.25, 0.5, 11025).unwrap();
+        assert!(max_error(&down, &expected, 100) < 1e-3);
+
+        let up = down.resample(44100).unwrap();
+        assert_eq!(up.len(), 4 * down.len());
+        assert!(max_error(&up, &wave, 400) < 1e-3);
+
+        // Linear interpolation is much rougher.
+        let linear = down.resample_with(44100, Interpolation::Linear).unwrap();
+        let error = max_error(&linear, &wave, 400);
+        assert!(error > 1e-3 && error < 0.05);
+    }
+
+    #[test]
+    fn wave_resample_filters_aliases() {
+        // 5 kHz is above the Nyquist frequency at 8 kHz.
+        let wave = SinSignal::<f64>::new(5000.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(0.25, 0.0, 44100)
+            .unwrap();
+        let rms = |w: &Wave<f64>| {
+            let ys = &w.ys()[200..w.len() - 200];
+            (ys.iter().map(|y| y * y).sum::<f64>() / ys.len() as f64).sqrt()
+        };
+
+        assert!(rms(&wave.resample(8000).unwrap()) < 0.01);
+        // Linear interpolation folds it to 3 kHz instead.
+        assert!(rms(&wave.resample_with(8000, Interpolation::Linear).unwrap()) > 0.5);
+    }
+
+    #[test]
+    fn wave_resample_edges() {
+        // The edge samples are held, so a constant stays constant to the end.
+        let constant = Wave::uniform(vec![1.0f64; 4], 0.0, 4).unwrap();
+        for interpolation in [
+            Interpolation::Nearest,
+            Interpolation::Linear,
+            Interpolation::Cubic,
+        ] {
+            let up = constant.resample_with(8, interpolation).unwrap();
+            assert_eq!(up.ys(), [1.0; 8]);
+        }
+        let sinc = constant.resample(8).unwrap();
+        assert!(sinc.ys().iter().all(|y| (y - 1.0).abs() < 0.01));
+
+        // A sinc needs at least one zero crossing on each side.
+        let flat = Interpolation::Sinc { half_width: 0 };
+        let rejected = Error::OutOfRange {
+            name: "half_width",
+            value: 0.0,
+        };
+        assert_eq!(constant.resample_with(8, flat), Err(rejected.clone()));
+        assert_eq!(
+            constant.clone().interpolated(flat, Extension::Zero).err(),
+            Some(rejected)
+        );
+
+        // Explicit times are followed, not taken to be a frame apart.
+        let ramp = Wave::new(vec![0.0f64, 1.0, 2.0], vec![0.0, 0.25, 1.0], 4).unwrap();
+        let up = ramp.resample_with(8, Interpolation::Linear).unwrap();
+        assert_eq!(
+            up.ys(),
+            [0.0, 0.5, 1.0, 1.0 + 1.0 / 6.0, 1.0 + 2.0 / 6.0, 1.5]
+        );
+        assert_eq!(up.start(), 0.0);
+    }
+
+    #[test]
+    fn wave_as_signal() {
+        let wave = Wave::new(vec![0.0, 1.0, 0.0, -1.0], vec![1.0, 1.25, 1.5, 1.75], 4).unwrap();
+        assert_eq!(wave.period(), 1.0);
+        assert_eq!(wave.periodicity(), Periodicity::Aperiodic);
+        // Linear between samples, silence outside.
+        assert_eq!(
+            wave.evaluate(&[1.0, 1.125, 1.625, 0.5, 2.5]),
+            vec![0.0, 0.5, -0.5, 0.0, 0.0]
+        );
+
+        let nearest = wave
+            .interpolated(Interpolation::Nearest, Extension::Periodic)
+            .unwrap();
+        assert_eq!(nearest.periodicity(), Periodicity::Periodic(1.0));
+        assert_eq!(
+            nearest.evaluate(&[1.1, 1.4, 0.25, 3.5]),
+            vec![0.0, 0.0, 1.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn wave_as_signal_explicit_times() {
+        // Samples 1 s apart at 4 Hz: the stored times are used, not i / 4.
+        let wave = Wave::new(vec![0.0, 1.0, 2.0], vec![0.0, 1.0, 2.0], 4).unwrap();
+        assert_eq!(wave.evaluate(&[1.0, 0.5, 1.75]), vec![1.0, 0.5, 1.75]);
+        // Outside, the samples are a frame apart.
+        assert_eq!(wave.evaluate(&[2.125, -0.125]), vec![1.0, 0.0]);
+
+        let nearest = wave
+            .interpolated(Interpolation::Nearest, Extension::Zero)
+            .unwrap();
+        assert_eq!(nearest.evaluate(&[0.4, 0.6, 1.9]), vec![0.0, 1.0, 2.0]);
+        assert_eq!(nearest.wave().time_axis().index(1.25, 4), 1.25);
+        assert!(nearest.wave().time_axis().index(f64::NAN, 4).is_nan());
+        // A NaN time doesn't panic.
+        assert_eq!(nearest.evaluate(&[f64::NAN]).len(), 1);
+    }
+
+    #[test]
+    fn wave_interpolation_modes() {
+        let signal = SinSignal::<f64>::new(50.0, 1.0, 0.0).unwrap();
+        let cycle = signal.make_wave(0.02, 0.0, 1000).unwrap();
+        let ts: Vec<f64> = (0..200).map(|i| 0.1 + i as f64 * 0.000_37).collect();
+        let expected = signal.evaluate(&ts);
+
+        let max_error = |interpolation| {
+            let periodic = cycle
+                .clone()
+                .interpolated(interpolation, Extension::Periodic)
+                .unwrap();
+            let ys = periodic.evaluate(&ts);
+            ys.iter()
+                .zip(&expected)
+                .map(|(y, e)| (y - e).abs())
+                .fold(0.0, f64::max)
+        };
+        let linear = max_error(Interpolation::Linear);
+        let cubic = max_error(Interpolation::Cubic);
+        let sinc = max_error(Interpolation::Sinc { half_width: 16 });
+        assert!(linear < 0.05 && cubic < linear / 4.0 && sinc < 1e-3);
+        assert!(max_error(Interpolation::Nearest) > linear);
+
+        // At the samples every mode gives the samples back.
+        let at_samples = cycle
+            .clone()
+            .interpolated(Interpolation::Cubic, Extension::Zero)
+            .unwrap()
+            .evaluate(&cycle.ts());
+        assert_eq!(at_samples, cycle.ys());
+    }
+
+    #[test]
+    fn wave_mixed_with_signals() {
+        let recorded = CosSignal::<f32>::new(5.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(1.0, 0.0, 1000)
+            .unwrap();
+        let mix = recorded
+            .interpolated(Interpolation::Cubic, Extension::Periodic)
+            .unwrap()
+            + SinSignal::new(5.0, 1.0, 0.0).unwrap();
+        // The recording repeats as a whole, every second.
+        assert!((mix.period() - 1.0).abs() < EPSILON_TIME);
+
+        let wave = mix.make_wave(0.2, 3.0, 40).unwrap();
+        assert_eq!(wave.len(), 8);
+        // cos(x) + sin(x) at x = π/4 + 2π k
+        let peak = wave.ys()[1];
+        assert!((peak - std::f32::consts::SQRT_2).abs() < EPSILON_LARGE);
+    }
+
+    #[test]
+    fn multichannel_interleave() {
+        let samples = [1.0, -1.0, 2.0, -2.0, 3.0, -3.0];
+        let wave = MultichannelWave::from_interleaved(&samples, 2, 0.5, 10).unwrap();
+        assert_eq!(wave.num_channels(), 2);
+        assert_eq!(wave.len(), 3);
+        assert_eq!(wave.channel_ys(0), [1.0, 2.0, 3.0]);
+        assert_eq!(wave.channel_ys(1), [-1.0, -2.0, -3.0]);
+        assert_eq!(wave.channel(1).ts()[..], [0.5, 0.6, 0.7]);
+        assert_eq!(wave.interleave(), samples);
+
+        let waves = wave.clone().into_waves();
+        assert_eq!(MultichannelWave::from_waves(waves).unwrap(), wave);
+    }
+
+    #[test]
+    fn multichannel_downmix() {
+        let mut wave = MultichannelWave::new(
+            vec![vec![1.0, 0.5], vec![0.0, 0.5], vec![2.0, -1.0]],
+            vec![0.0, 0.1],
+            10,
+        )
+        .unwrap();
+        assert_eq!(wave.downmix().ys(), [1.0, 0.0]);
+        assert_eq!(
+            wave.downmix_with(&[1.0, 1.0, 0.0]).unwrap().ys(),
+            [1.0, 1.0]
+        );
+
+        wave.channel_ys_mut(2)[1] = 1.0;
+        assert_eq!(
+            wave.downmix_with(&[0.0, 0.0, 1.0]).unwrap().ys(),
+            [2.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn signal_pan_constant_power() {
+        let center = SinSignal::<f32>::new(10.0, 1.0, 0.0).unwrap().pan(0.0);
+        let (left, right) = center.gains();
+        assert!((left - right).abs() < EPSILON);
+        assert!((left * left + right * right - 1.0).abs() < EPSILON);
+
+        let hard_left = CosSignal::<f32>::new(10.0, 1.0, 0.0).unwrap().pan(-1.0);
+        let wave = hard_left.make_wave(0.1, 0.0, 100).unwrap();
+        assert_eq!(wave.num_channels(), 2);
+        assert_eq!(
+            wave.channel_ys(0),
+            CosSignal::new(10.0, 1.0, 0.0)
+                .unwrap()
+                .make_wave(0.1, 0.0, 100)
+                .unwrap()
+                .ys()
+        );
+        assert!(wave.channel_ys(1).iter().all(|y| y.abs() < EPSILON));
+
+        // Out-of-range positions are clamped.
+        assert_eq!(
+            SilentSignal.pan(3.0f32).gains(),
+            SilentSignal.pan(1.0).gains()
+        );
+    }
+
+    #[test]
+    fn wave_quantize_levels() {
+        let wave = Wave::new(vec![0.3, -0.3, 0.99, -1.5, 1.5], vec![0.0; 5], 1).unwrap();
+        let q: Wave<f64> = wave.quantize(3).unwrap();
+        // 3 bits: steps of 0.25, from -1 to 0.75.
+        assert_eq!(q.ys(), [0.25, -0.25, 0.75, -1.0, 0.75]);
+
+        let floor = wave
+            .quantize_with(3, Rounding::Floor, Dither::None)
+            .unwrap();
+        assert_eq!(floor.ys(), [0.25, -0.5, 0.75, -1.0, 0.75]);
+        let trunc = wave
+            .quantize_with(3, Rounding::TowardZero, Dither::None)
+            .unwrap();
+        assert_eq!(trunc.ys(), [0.25, -0.25, 0.75, -1.0, 0.75]);
+    }
+
+    #[test]
+    fn wave_quantize_snr() {
+        let wave = SinSignal::<f64>::new(997.0, 0.99, 0.0)
+            .unwrap()
+            .make_wave(0.5, 0.0, 44100)
+            .unwrap();
+        // A sine just below full scale is 0.09 dB under the theory.
+        for bits in [8, 16] {
+            let snr = wave.quantize(bits).unwrap().snr(&wave).unwrap();
+            assert!(
+                (snr - quantization_snr(bits)).abs() < 1.0,
+                "{bits} bits: {snr}"
+            );
+        }
+        assert!((quantization_snr(16) - 98.09).abs() < 0.01);
+
+        // TPDF dither costs about 4.8 dB.
+        let tpdf = wave
+            .quantize_with(8, Rounding::Nearest, Dither::Tpdf)
+            .unwrap();
+        let snr = tpdf.snr(&wave).unwrap();
+        assert!((snr - (quantization_snr(8) - 4.77)).abs() < 1.0, "{snr}");
+        assert_eq!(
+            tpdf.ys(),
+            wave.quantize_with(8, Rounding::Nearest, Dither::Tpdf)
+                .unwrap()
+                .ys()
+        );
+    }
+
+    #[test]
+    fn wave_quantize_noise_shaping() {
+        let wave = SinSignal::<f64>::new(997.0, 0.5, 0.0)
+            .unwrap()
+            .make_wave(0.5, 0.0, 44100)
+            .unwrap();
+        // Low-frequency noise: the error averaged over blocks of 64 samples.
+        let low_noise = |dither| {
+            let q = wave.quantize_with(8, Rounding::Nearest, dither).unwrap();
+            let errors: Vec<f64> = q.ys().iter().zip(wave.ys()).map(|(y, x)| y - x).collect();
+            let means: Vec<f64> = errors
+                .chunks(64)
+                .map(|c| c.iter().sum::<f64>() / c.len() as f64)
+                .collect();
+            (means.iter().map(|m| m * m).sum::<f64>() / means.len() as f64).sqrt()
+        };
+        assert!(low_noise(Dither::NoiseShaped) < low_noise(Dither::Tpdf) / 4.0);
+    }
+
+    #[test]
+    fn stream_fill_matches_make_wave() {
+        let signal = CosSignal::<f32>::new(440.0, 1.0, 0.0).unwrap()
+            * SinSignal::new(3.0, 1.0, 0.0).unwrap();
+        let wave = signal.make_wave(0.2, 0.5, 11025).unwrap();
+
+        let mut stream = signal.stream(0.5, 11025).unwrap();
+        let mut ys = Vec::new();
+        // Uneven blocks, so block edges fall anywhere in a cycle.
+        for size in [1, 7, 100, 64, 333].iter().cycle() {
+            let remaining = wave.len() - ys.len();
+            if remaining == 0 {
+                break;
+            }
+            let mut block = vec![0.0; (*size).min(remaining)];
+            stream.fill(&mut block);
+            ys.extend(block);
+        }
+        assert_eq!(ys, wave.ys());
+        assert!((stream.time() - 0.7).abs() < EPSILON_TIME);
+    }
+
+    #[test]
+    fn stream_iterator_and_fill() {
+        let wave = SinSignal::<f32>::new(100.0, 1.0, 0.0)
+            .unwrap()
+            .make_wave(0.5, 0.0, 8000)
+            .unwrap();
+        let mut stream =
+            Stream::new(SinSignal::new(100.0, 1.0, 0.0).unwrap().boxed(), 0.0, 8000).unwrap();
+
+        let mut ys: Vec<f32> = stream.by_ref().take(10).collect();
+        let mut block = [0.0; 2000];
+        stream.fill(&mut block);
+        ys.extend(block);
+        ys.extend(stream.by_ref().take(wave.len() - ys.len()));
+        assert_eq!(ys, wave.ys());
+        assert!((stream.time() - 0.5).abs() < EPSILON_TIME);
+    }
+}
+
+#[cfg(test)]
+mod render {
+    use crate::render::{
+        BACKEND_ENV, Backend, MinMaxPyramid, Series, Style, Times, decimate, render_terminal,
+        render_terminal_channels,
+    };
+
+    #[test]
+    fn terminal_plot_size() {
+        let ts: Vec<f64> = (0..100).map(|i| i as f64 / 100.0).collect();
+        let ys: Vec<f32> = ts
+            .iter()
+            .map(|t| (std::f64::consts::TAU * t).sin() as f32)
+            .collect();
+        let plot = render_terminal(&[Series::new("sin", &ts, &ys)], 60, 10);
+        let lines: Vec<&str> = plot.lines().collect();
+
+        // 10 rows of plot, the x axis and its labels
+        assert_eq!(lines.len(), 12);
+        for line in &lines[..11] {
+            assert_eq!(line.chars().count(), 60);
+        }
+    }
+
+    #[test]
+    fn terminal_plot_channels() {
+        let ts = vec![0.0, 1.0, 2.0];
+        let (left, right) = (vec![0.0, 1.0, 0.0], vec![0.0, -1.0, 0.0]);
+        let channels = [
+            Series::new("left", &ts, &left),
+            Series::new("right", &ts, &right),
+        ];
+        let plot = render_terminal_channels(&channels, 40, 10);
+        let lines: Vec<&str> = plot.lines().collect();
+
+        // Per channel: its name, 5 rows of plot, the x axis and its labels.
+        assert_eq!(lines.len(), 16);
+        assert_eq!(lines[0].trim(), "left");
+        assert_eq!(lines[8].trim(), "right");
+        assert_eq!(
+            &lines[1..8].join("\n"),
+            render_terminal(&channels[..1], 40, 5).trim_end()
+        );
+    }
+
+    #[test]
+    fn terminal_plot_ticks() {
+        let ts = vec![0.0, 1.0, 2.0];
+        let ys = vec![-1.0, 1.0, -1.0];
+        let plot = render_terminal(&[Series::new("wave", &ts, &ys)], 40, 5);
+        let lines: Vec<&str> = plot.lines().collect();
+
+        assert!(lines[0].trim_start().starts_with("1.00┤"));
+        assert!(lines[4].trim_start().starts_with("-1.00┤"));
+        assert!(lines[5].contains('└') && lines[5].contains('┬'));
+        assert!(lines[6].trim_start().starts_with("0.000"));
+        assert!(lines[6].trim_end().ends_with("2.00"));
+    }
+
+    #[test]
+    fn terminal_plot_draws_every_column() {
+        // A connected line must leave no gap between the first and last sample.
+        let ts = vec![0.0, 1.0];
+        let ys = vec![0.0, 1.0];
+        let plot = render_terminal(&[Series::new("wave", &ts, &ys)], 30, 4);
+        for line in plot.lines().take(4) {
+            let plot_area: String = line.chars().skip(10).collect();
+            assert!(plot_area.chars().any(|c| c != '\u{2800}'));
+        }
+    }
+
+    #[test]
+    fn terminal_plot_stems() {
+        // Two samples far apart: stems leave the columns between them empty,
+        // a line does not.
+        let ts = vec![0.0, 1.0];
+        let ys = vec![1.0, 1.0];
+        let is_blank = |plot: &str| {
+            plot.lines().take(4).all(|line| {
+                let row: Vec<char> = line.chars().skip(10).collect();
+                row[5..row.len() - 5].iter().all(|&c| c == '\u{2800}')
+            })
+        };
+
+        let line = render_terminal(&[Series::new("wave", &ts, &ys)], 40, 4);
+        let stems = Series::new("wave", &ts, &ys).style(Style::Stems);
+        let stems = render_terminal(&[stems], 40, 4);
+        assert!(!is_blank(&line));
+        assert!(is_blank(&stems));
+
+        // Stems reach down to zero, which becomes the bottom of the plot.
+        assert!(
+            stems
+                .lines()
+                .nth(3)
+                .unwrap()
+                .trim_start()
+                .starts_with("0.000┤")
+        );
+        let bottom: String = stems.lines().nth(3).unwrap().chars().skip(10).collect();
+        assert_ne!(bottom.chars().next(), Some('\u{2800}'));
+    }
+
+    #[test]
+    fn terminal_plot_markers_over_line() {
+        let dense_ts: Vec<f64> = (0..200).map(|i| i as f64 / 200.0).collect();
+        let dense_ys: Vec<f32> = dense_ts.iter().map(|t| (t * 2.0 - 1.0) as f32).collect();
+        let ts = vec![0.0, 0.5];
+        let ys = vec![-1.0, 0.0];
+
+        let markers = Series::new("samples", &ts, &ys).style(Style::Markers);
+        let alone = render_terminal(&[markers], 40, 4);
+        let dots = |plot: &str| {
+            plot.lines()
+                .take(4)
+                .flat_map(|l| l.chars().skip(10))
+                .map(|c| (c as u32 - 0x2800).count_ones())
+                .sum::<u32>()
+        };
+        assert_eq!(dots(&alone), 2);
+
+        let over = [Series::new("signal", &dense_ts, &dense_ys), markers];
+        assert!(dots(&render_terminal(&over, 40, 4)) > 2);
+    }
+
+    #[test]
+    fn terminal_plot_empty() {
+        assert_eq!(render_terminal::<f32>(&[], 40, 5), "(empty wave)\n");
+        let empty = Series::<f32>::new("wave", &[], &[]);
+        assert_eq!(render_terminal(&[empty], 40, 5), "(empty wave)\n");
+    }
+
+    #[test]
+    fn backend_from_env() {
+        // SAFETY: no other test reads or writes this variable.
+        unsafe { std::env::set_var(BACKEND_ENV, "native") };
+        assert_eq!(Backend::from_env(), Backend::Native);
+        unsafe { std::env::set_var(BACKEND_ENV, "terminal") };
+        assert!(matches!(Backend::from_env(), Backend::Terminal { .. }));
+        unsafe { std::env::remove_var(BACKEND_ENV) };
+    }
+
+    #[test]
+    fn decimate_keeps_peaks() {
+        let n = 1_000_000;
+        let ts: Vec<f64> = (0..n).map(|i| i as f64 / 44100.0).collect();
+        let mut ys: Vec<f32> = (0..n).map(|i| ((i % 7) as f32 - 3.0) / 10.0).collect();
+        ys[123_457] = 5.0;
+        ys[876_543] = -4.0;
+
+        let range = (0.0, *ts.last().unwrap());
+        let points = decimate(&ts, &ys, range, 800);
+
+        assert!(points.len() <= 2 * 800 + 4);
+        assert!(points.iter().any(|p| p[1] == 5.0));
+        assert!(points.iter().any(|p| p[1] == -4.0));
+        assert!(points.windows(2).all(|w| w[0][0] <= w[1][0]));
+    }
+
+    #[test]
+    fn decimate_follows_visible_range() {
+        let ts: Vec<f64> = (0..10_000).map(|i| i as f64).collect();
+        let ys: Vec<f32> = ts.iter().map(|t| t.sin() as f32).collect();
+
+        let points = decimate(&ts, &ys, (1000.0, 2000.0), 100);
+        // The sample right before and right after the range are kept.
+        assert_eq!(points.first().unwrap()[0], 999.0);
+        assert_eq!(points.last().unwrap()[0], 2001.0);
+        assert!(points.len() <= 2 * 100 + 4);
+    }
+
+    #[test]
+    fn uniform_times_plot_like_explicit_ones() {
+        let ys: Vec<f32> = (0..500).map(|i| (i as f32 * 0.05).sin()).collect();
+        let ts: Vec<f64> = (0..500).map(|i| 0.5 + i as f64 / 100.0).collect();
+        let uniform = Times::Uniform {
+            start: 0.5,
+            framerate: 100,
+        };
+        assert_eq!(
+            render_terminal(&[Series::with_times("wave", uniform, &ys)], 60, 8),
+            render_terminal(&[Series::new("wave", &ts, &ys)], 60, 8)
+        );
+    }
+
+    #[test]
+    fn pyramid_keeps_peaks() {
+        let n = 1_000_000;
+        let mut ys: Vec<f32> = (0..n).map(|i| ((i % 7) as f32 - 3.0) / 10.0).collect();
+        ys[123_457] = 5.0;
+        ys[876_543] = -4.0;
+        let times = Times::Uniform {
+            start: 0.0,
+            framerate: 44100,
+        };
+        let series = Series::with_times("wave", times, &ys);
+        let pyramid = MinMaxPyramid::new(&ys);
+
+        let end = (n - 1) as f64 / 44100.0;
+        let points = pyramid.decimate(&series, (0.0, end), 800);
+        assert!(points.len() <= 4 * 800 + 6);
+        assert!(points.iter().any(|p| p[1] == 5.0));
+        assert!(points.iter().any(|p| p[1] == -4.0));
+        assert!(points.windows(2).all(|w| w[0][0] < w[1][0]));
+        assert_eq!(points.first().unwrap()[0], 0.0);
+        assert_eq!(points.last().unwrap()[0], end);
+
+        // Zoomed in, the samples just outside the range are kept.
+        let points = pyramid.decimate(&series, (1.0, 2.0), 100);
+        assert_eq!(points.first().unwrap()[0], 44099.0 / 44100.0);
+        assert_eq!(points.last().unwrap()[0], 88201.0 / 44100.0);
+        // The peaks are further on.
+        assert!(points.iter().all(|p| p[1].abs() < 0.31));
+    }
+
+    #[test]
+    fn decimate_zoomed_in_returns_samples() {
+        let ts: Vec<f64> = (0..10_000).map(|i| i as f64).collect();
+        let ys: Vec<f32> = ts.iter().map(|t| t.sin() as f32).collect();
+
+        let points = decimate(&ts, &ys, (10.0, 20.0), 100);
+        let expected: Vec<[f64; 2]> = (9..=21).map(|i| [ts[i], ys[i] as f64]).collect();
+        assert_eq!(points, expected);
+    }
+}
+
+#[cfg(test)]
+mod dft {
+    use crate::book::dft::{
+        Matrix, analyze, analyze_dct, analyze_dct_orthogonal, analyze_orthogonal, dct_iv,
+        dct_iv_grid, dft, dft_grid, idft, inverse_dct_iv, synthesize, synthesize_dct,
+    };
+    use crate::book::{Complex, ComplexSinusoid, CosSignal, Mix, Signal, Wave};
+    use crate::error::Error;
+
+    const EPSILON: f64 = 1e-9;
+
+    // The example of chapter 7.
+    const AMPS: [f64; 4] = [0.6, 0.25, 0.1, 0.05];
+    const FS: [f64; 4] = [100.0, 200.0, 300.0, 400.0];
+    const FRAMERATE: u64 = 11025;
+
+    fn close(a: &[Complex<f64>], b: &[Complex<f64>], tolerance: f64) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).norm() < tolerance)
+    }
+
+    fn phased_amps() -> Vec<Complex<f64>> {
+        AMPS.iter()
+            .enumerate()
+            .map(|(k, &amp)| Complex::from_polar(amp, 0.3 * k as f64))
+            .collect()
+    }
+
+    #[test]
+    fn synthesize_matches_complex_sinusoids() {
+        let amps = phased_amps();
+        let ts: Vec<f64> = (0..64).map(|i| i as f64 / FRAMERATE as f64).collect();
+        let wave = synthesize(&amps, &FS, &ts).unwrap();
+        assert_eq!(wave.framerate, FRAMERATE);
+
+        let mut expected = vec![Complex::new(0.0, 0.0); ts.len()];
+        for (amp, &freq) in amps.iter().zip(&FS) {
+            let signal = ComplexSinusoid::new(freq, amp.norm(), amp.arg()).unwrap();
+            for (y, s) in expected.iter_mut().zip(signal.evaluate(&ts)) {
+                *y += s;
+            }
+        }
+        assert!(close(wave.ys(), &expected, EPSILON));
+
+        // The real part is a sum of cosines.
+        let cosines: Mix<f64> = amps
+            .iter()
+            .zip(&FS)
+            .map(|(amp, &freq)| (1.0, CosSignal::new(freq, amp.norm(), amp.arg()).unwrap()))
+            .collect();
+        let real = Wave::new(cosines.evaluate(&ts), ts.clone(), FRAMERATE).unwrap();
+        wave.real().assert_close(&real, EPSILON);
+    }
+
+    #[test]
+    fn analyze_solves_the_system() {
+        let amps = phased_amps();
+        let ts: Vec<f64> = (0..4).map(|i| i as f64 / FRAMERATE as f64).collect();
+        let wave = synthesize(&amps, &FS, &ts).unwrap();
+
+        let result = analyze(&wave, &FS).unwrap();
+        assert!(close(&result, &amps, 1e-6));
+
+        // Too few samples to tell the frequencies apart.
+        assert_eq!(
+            analyze(&wave, &[100.0, 200.0, 300.0, 400.0, 500.0]),
+            Err(Error::LengthMismatch {
+                expected: 4,
+                found: 5
+            })
+        );
+    }
+
+    #[test]
+    fn analyze_orthogonal_on_dft_grid() {
+        let (ts, fs) = dft_grid(8);
+        let amps: Vec<Complex<f64>> = (0..8)
+            .map(|k| Complex::new(k as f64, 1.0 - k as f64 / 2.0))
+            .collect();
+        let wave = synthesize(&amps, &fs, &ts).unwrap();
+        assert_eq!(wave.framerate, 8);
+
+        assert!(close(&analyze_orthogonal(&wave, &fs), &amps, EPSILON));
+        assert!(close(&analyze(&wave, &fs).unwrap(), &amps, EPSILON));
+    }
+
+    #[test]
+    fn dft_roundtrip() {
+        let n = 16;
+        let ts: Vec<f64> = (0..n).map(|i| i as f64 / n as f64).collect();
+        let ys = ComplexSinusoid::new(3.0, 1.0, 0.0).unwrap().evaluate(&ts);
+
+        let spectrum = dft(&ys);
+        for (k, amp) in spectrum.iter().enumerate() {
+            let expected = if k == 3 { n as f64 } else { 0.0 };
+            assert!((amp.norm() - expected).abs() < EPSILON, "bin {k}: {amp}");
+        }
+        assert!(close(&idft(&spectrum), &ys, EPSILON));
+    }
+
+    #[test]
+    fn synthesize_dct_matches_cosines() {
+        let ts: Vec<f64> = (0..32).map(|i| i as f64 / FRAMERATE as f64).collect();
+        let wave = synthesize_dct(&AMPS, &FS, &ts).unwrap();
+        let cosines: Mix<f64> = AMPS
+            .iter()
+            .zip(&FS)
+            .map(|(&amp, &freq)| (amp, CosSignal::new(freq, 1.0, 0.0).unwrap()))
+            .collect();
+        let expected = Wave::new(cosines.evaluate(&ts), ts.clone(), FRAMERATE).unwrap();
+        wave.assert_close(&expected, EPSILON);
+
+        let ts: Vec<f64> = (0..4)
+            .map(|i| (i as f64 + 0.5) / FRAMERATE as f64)
+            .collect();
+        let wave = synthesize_dct(&AMPS, &FS, &ts).unwrap();
+        let result = analyze_dct(&wave, &FS).unwrap();
+        for (r, a) in result.iter().zip(&AMPS) {
+            assert!((r - a).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn dct_iv_roundtrip() {
+        let ys: [f64; 6] = [0.5, -0.25, 1.0, 0.0, 0.75, -1.0];
+        let amps = dct_iv(&ys);
+        let back = inverse_dct_iv(&amps);
+        for (b, y) in back.iter().zip(&ys) {
+            assert!((b - y).abs() < EPSILON);
+        }
+
+        let (ts, fs) = dct_iv_grid(ys.len());
+        let wave = synthesize_dct(&ys, &fs, &ts).unwrap();
+        for (r, y) in analyze_dct_orthogonal(&wave, &fs).iter().zip(&ys) {
+            assert!((r - y).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn matrix_solve() {
+        let m: Matrix<f64> = Matrix::from_fn(2, 2, |i, j| [[2.0, 1.0], [1.0, 3.0]][i][j]);
+        let x = m.solve(&[3.0, 5.0]).unwrap();
+        assert!((x[0] - 0.8).abs() < EPSILON && (x[1] - 1.4).abs() < EPSILON);
+        assert_eq!(m.mul_vec(&[1.0, 1.0]).unwrap(), vec![3.0, 4.0]);
+        assert_eq!(m.conj_transpose()[(0, 1)], 1.0);
+
+        let singular: Matrix<f64> = Matrix::from_fn(2, 2, |_, _| 1.0);
+        assert_eq!(singular.solve(&[1.0, 2.0]), Err(Error::Singular));
+        assert!(
+            Matrix::<f64>::from_fn(2, 3, |_, _| 1.0)
+                .solve(&[1.0, 2.0])
+                .is_err()
+        );
+        assert!(m.mul_vec(&[1.0]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod stats {
+    use crate::book::stats::Statistic;
+    use crate::book::{CosSignal, Signal, SinSignal, Wave};
+    use crate::error::Error;
+
+    const EPSILON: f64 = 1e-6;
+
+    fn sine(freq: f64, amp: f64, duration: f64, framerate: u64) -> Wave<f64> {
+        SinSignal::new(freq, amp, 0.0)
+            .unwrap()
+            .make_wave(duration, 0.0, framerate)
+            .unwrap()
+    }
+
+    #[test]
+    fn levels_of_a_sine() {
+        let wave = sine(100.0, 1.0, 1.0, 8000);
+        assert!((wave.rms() - 0.5f64.sqrt()).abs() < EPSILON);
+        assert!((wave.peak() - 1.0).abs() < EPSILON);
+        assert!((wave.crest_factor() - 2.0f64.sqrt()).abs() < EPSILON);
+        assert!((wave.dbfs() + 3.0103).abs() < 1e-3);
+        assert!((wave.energy() - 4000.0).abs() < EPSILON);
+        assert!(wave.dc_offset().abs() < EPSILON);
+
+        // Off the samples, the zero crossings are clear-cut.
+        let cosine = CosSignal::<f64>::new(100.0, 1.0, 0.1)
+            .unwrap()
+            .make_wave(1.0, 0.0, 8000)
+            .unwrap();
+        assert_eq!(cosine.zero_crossing_rate(), 200.0);
+    }
+
+    #[test]
+    fn levels_of_silence() {
+        let wave = Wave::new(vec![0.0f32; 10], (0..10).map(|i| i as f64).collect(), 1).unwrap();
+        assert_eq!(wave.rms(), 0.0);
+        assert_eq!(wave.crest_factor(), 0.0);
+        assert_eq!(wave.dbfs(), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn dc_offset() {
+        let wave = (CosSignal::new(50.0, 1.0, 0.0).unwrap() + 0.25f64)
+            .make_wave(1.0, 0.0, 1000)
+            .unwrap();
+        assert!((wave.dc_offset() - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    fn loudness_of_a_full_scale_sine() {
+        // The calibration of BS.1770: a 1 kHz sine at full scale reads -3.01 LUFS.
+        let loudness = sine(1000.0, 1.0, 2.0, 48000).loudness().unwrap();
+        assert!((loudness + 3.01).abs() < 0.05, "{loudness}");
+
+        // Halving the amplitude takes 6 dB off, whatever the framerate.
+        let quieter = sine(1000.0, 0.5, 2.0, 44100).loudness().unwrap();
+        assert!((quieter - loudness + 6.02).abs() < 0.05, "{quieter}");
+    }
+
+    #[test]
+    fn loudness_gates_silence() {
+        let tone = sine(1000.0, 0.5, 1.0, 48000);
+        let mut ys = tone.ys().to_vec();
+        ys.extend(vec![0.0; 48000]);
+        let ts = (0..ys.len()).map(|i| i as f64 / 48000.0).collect();
+        let padded = Wave::new(ys, ts, 48000).unwrap();
+
+        // The silent blocks are gated out, but not the three that overlap the
+        // end of the tone: 7 whole blocks and 0.75 + 0.5 + 0.25 of one.
+        let (a, b) = (tone.loudness().unwrap(), padded.loudness().unwrap());
+        let expected = a + 10.0 * (8.5f64 / 10.0).log10();
+        assert!((b - expected).abs() < 0.05, "{a} {b}");
+        assert_eq!(
+            sine(1000.0, 1.0, 0.2, 48000).loudness(),
+            Ok(f64::NEG_INFINITY)
+        );
+    }
+
+    #[test]
+    fn windowed_statistics() {
+        let wave = sine(10.0, 1.0, 1.0, 1000);
+        let rms = wave.windowed(Statistic::Rms, 0.1, 0.05).unwrap();
+        assert_eq!(rms.len(), 19);
+        assert!(rms.iter().all(|r| (r - 0.5f64.sqrt()).abs() < EPSILON));
+
+        let momentary = sine(1000.0, 1.0, 1.0, 48000)
+            .windowed(Statistic::Loudness, 0.4, 0.1)
+            .unwrap();
+        assert_eq!(momentary.len(), 7);
+        assert!(momentary.iter().skip(1).all(|l| (l + 3.01).abs() < 0.05));
+
+        assert!(wave.windowed(Statistic::Rms, 0.0, 0.1).is_err());
+        assert!(matches!(
+            wave.windowed(Statistic::Loudness, 0.1, 0.1),
+            Err(Error::OutOfRange {
+                name: "framerate",
+                ..
+            })
+        ));
+    }
 }